@@ -0,0 +1,60 @@
+//! Where idempotent insertions go when their text is not in the buffer yet.
+use crate::pattern::Pattern;
+
+/// Insertion point for [`ensure_block`](crate::Editor::ensure_block) and
+/// friends, always at a line boundary.
+///
+/// `Before` / `After` use the line holding the first match of the pattern;
+/// when nothing matches, the text is appended at the end instead.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Anchor<'a> {
+    /// At the start of the buffer.
+    Start,
+    /// At the end of the buffer (default).
+    #[default]
+    End,
+    /// Before the line containing the pattern.
+    Before(Pattern<'a>),
+    /// After the line containing the pattern.
+    After(Pattern<'a>),
+}
+
+impl Anchor<'_> {
+    /// Byte offset to insert at, and whether a line ending must be inserted
+    /// first because the preceding line is unterminated.
+    pub(crate) fn locate(&self, buf: &str) -> (usize, bool) {
+        let found = match self {
+            Anchor::Start => return (0, false),
+            Anchor::End => None,
+            Anchor::Before(pat) | Anchor::After(pat) => pat.find_at(buf, 0),
+        };
+        let Some(m) = found else {
+            return (buf.len(), !buf.is_empty() && !buf.ends_with('\n'));
+        };
+        if let Anchor::Before(_) = self {
+            return (buf[..m.start].rfind('\n').map_or(0, |i| i + 1), false);
+        }
+        match buf[m.end..].find('\n') {
+            Some(i) => (m.end + i + 1, false),
+            None => (buf.len(), !buf.ends_with('\n')),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn anchors_sit_on_line_boundaries() {
+        let buf = "a = 1\n[x]\nb = 2";
+        let at = |anchor: Anchor<'_>| anchor.locate(buf);
+        assert_eq!(at(Anchor::Start), (0, false));
+        assert_eq!(at(Anchor::End), (buf.len(), true));
+        assert_eq!(at(Anchor::Before("x]".into())), (6, false));
+        assert_eq!(at(Anchor::After("[x".into())), (10, false));
+        assert_eq!(at(Anchor::After("b =".into())), (buf.len(), true));
+        assert_eq!(at(Anchor::Before("zzz".into())), (buf.len(), true));
+        assert_eq!(Anchor::End.locate(""), (0, false));
+    }
+}
@@ -0,0 +1,125 @@
+//! Crash-safe writes: sibling temp file → flush → atomic rename.
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::lock;
+use crate::metadata::{self, Preserve};
+
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Knobs for [`write`].
+#[derive(Debug, Default)]
+pub(crate) struct Options {
+    /// Sync the file and its directory.
+    pub fsync: bool,
+    /// Exact permission bits when `path` does not exist yet (Unix only).
+    pub mode: Option<u32>,
+    /// Metadata copied from the file being replaced.
+    pub preserve: Preserve,
+    /// Take an advisory lock on the new file before it replaces the old one.
+    pub lock: bool,
+}
+
+/// Write `bytes` to `path` atomically.
+///
+/// The data goes to a hidden temp file in the same directory, which is then
+/// renamed over `path`. Readers see either the old or the new contents, never
+/// a truncated file. With `fsync` the temp file is synced before the
+/// rename and the parent directory afterwards, so the rename survives a crash.
+///
+/// With `lock`, the new file is returned still open and locked, so that an
+/// advisory lock survives the swap.
+pub(crate) fn write(path: &Path, bytes: &[u8], opts: &Options) -> io::Result<Option<File>> {
+    let (tmp_path, mut tmp) = create_temp(path)?;
+
+    let result = (|| {
+        match fs::metadata(path) {
+            Ok(original) => metadata::copy(&original, path, &tmp, &opts.preserve)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                if let Some(mode) = opts.mode {
+                    set_mode(&tmp, mode)?;
+                }
+            }
+            Err(e) => return Err(e),
+        }
+        tmp.write_all(bytes)?;
+        tmp.flush()?;
+        if opts.fsync {
+            tmp.sync_all()?;
+        }
+        if opts.lock {
+            lock::relock(&tmp)?;
+        }
+        fs::rename(&tmp_path, path)?;
+        if opts.fsync {
+            sync_dir(path)?;
+        }
+        Ok(opts.lock.then_some(tmp))
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+/// Create a fresh `.<name>.<pid>.<n>.tmp` next to `path`.
+fn create_temp(path: &Path) -> io::Result<(PathBuf, File)> {
+    let dir = parent_dir(path);
+    let name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?
+        .to_string_lossy();
+
+    loop {
+        let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let tmp_path = dir.join(format!(".{name}.{}.{n}.tmp", process::id()));
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&tmp_path)
+        {
+            Ok(file) => return Ok((tmp_path, file)),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Directory containing `path` (`.` for bare file names).
+fn parent_dir(path: &Path) -> &Path {
+    match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    }
+}
+
+/// Set exact permission bits (not masked by the umask).
+#[cfg(unix)]
+pub(crate) fn set_mode(file: &File, mode: u32) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    file.set_permissions(fs::Permissions::from_mode(mode))
+}
+
+/// Permission bits are a Unix concept; ignored elsewhere.
+#[cfg(not(unix))]
+pub(crate) fn set_mode(_file: &File, _mode: u32) -> io::Result<()> {
+    Ok(())
+}
+
+/// Persist the directory entry created by the rename.
+#[cfg(unix)]
+fn sync_dir(path: &Path) -> io::Result<()> {
+    File::open(parent_dir(path))?.sync_all()
+}
+
+/// Directories cannot be opened for syncing on this platform.
+#[cfg(not(unix))]
+fn sync_dir(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
@@ -0,0 +1,195 @@
+//! Backup-on-save policies: `file.bak`, `file.~N~` and timestamped copies.
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// What [`save`](crate::Editor::save) does with the previous file contents.
+///
+/// A `keep` of `0` disables pruning.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Backup {
+    /// No backup (default).
+    #[default]
+    None,
+    /// sed-style single `file.bak`, overwritten on every save.
+    Simple,
+    /// Emacs-style numbered `file.~1~`, `file.~2~`, …; keeps the newest `keep`.
+    Numbered {
+        /// Maximum number of backups to retain.
+        keep: usize,
+    },
+    /// `dir/file.20250429T101500.000000000Z`; keeps the newest `keep`.
+    Timestamped {
+        /// Directory holding the backups (created on demand).
+        dir: PathBuf,
+        /// Maximum number of backups to retain.
+        keep: usize,
+    },
+}
+
+/// Copy `target` according to `policy`; returns the backup's path.
+///
+/// Nothing happens (and `Ok(None)` is returned) if `target` does not exist.
+pub(crate) fn create(policy: &Backup, target: &Path) -> io::Result<Option<PathBuf>> {
+    if matches!(policy, Backup::None) || !target.exists() {
+        return Ok(None);
+    }
+    let name = file_name(target)?;
+    let dest = match policy {
+        Backup::None => unreachable!(),
+        Backup::Simple => target.with_file_name(format!("{name}.bak")),
+        Backup::Numbered { .. } => {
+            let next = numbered(target)?.last().map_or(1, |(n, _)| n + 1);
+            target.with_file_name(format!("{name}.~{next}~"))
+        }
+        Backup::Timestamped { dir, .. } => {
+            fs::create_dir_all(dir)?;
+            let mut now = SystemTime::now();
+            loop {
+                let dest = dir.join(format!("{name}.{}", timestamp(now)));
+                if !dest.exists() {
+                    break dest;
+                }
+                now += std::time::Duration::from_nanos(1);
+            }
+        }
+    };
+    fs::copy(target, &dest)?;
+
+    if let Backup::Numbered { keep, .. } | Backup::Timestamped { keep, .. } = policy {
+        let all = list(policy, target)?;
+        if *keep > 0 && all.len() > *keep {
+            for old in &all[..all.len() - keep] {
+                fs::remove_file(old)?;
+            }
+        }
+    }
+    Ok(Some(dest))
+}
+
+/// Existing backups of `target` under `policy`, oldest first.
+pub(crate) fn list(policy: &Backup, target: &Path) -> io::Result<Vec<PathBuf>> {
+    match policy {
+        Backup::None => Ok(Vec::new()),
+        Backup::Simple => {
+            let bak = target.with_file_name(format!("{}.bak", file_name(target)?));
+            Ok(bak.exists().then_some(bak).into_iter().collect())
+        }
+        Backup::Numbered { .. } => Ok(numbered(target)?.into_iter().map(|(_, p)| p).collect()),
+        Backup::Timestamped { dir, .. } => {
+            let prefix = format!("{}.", file_name(target)?);
+            let mut found = Vec::new();
+            for entry in read_dir(dir)? {
+                let fname = entry.file_name();
+                let fname = fname.to_string_lossy();
+                if fname.strip_prefix(&prefix).is_some_and(is_timestamp) {
+                    found.push(entry.path());
+                }
+            }
+            found.sort();
+            Ok(found)
+        }
+    }
+}
+
+/// `file.~N~` siblings of `target`, sorted by `N`.
+fn numbered(target: &Path) -> io::Result<Vec<(u64, PathBuf)>> {
+    let prefix = format!("{}.~", file_name(target)?);
+    let dir = match target.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+    let mut found = Vec::new();
+    for entry in read_dir(dir)? {
+        let fname = entry.file_name();
+        let n = fname
+            .to_string_lossy()
+            .strip_prefix(&prefix)
+            .and_then(|rest| rest.strip_suffix('~'))
+            .and_then(|n| n.parse::<u64>().ok());
+        if let Some(n) = n {
+            found.push((n, entry.path()));
+        }
+    }
+    found.sort();
+    Ok(found)
+}
+
+/// Directory entries of `dir`; a missing directory has none.
+fn read_dir(dir: &Path) -> io::Result<Vec<fs::DirEntry>> {
+    match fs::read_dir(dir) {
+        Ok(entries) => entries.collect(),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+fn file_name(path: &Path) -> io::Result<String> {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))
+}
+
+/// UTC `YYYYMMDDTHHMMSS.nnnnnnnnnZ`; sorts lexicographically by time.
+fn timestamp(t: SystemTime) -> String {
+    let d = t.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = d.as_secs();
+    let (y, m, day) = civil_from_days((secs / 86_400) as i64);
+    let rem = secs % 86_400;
+    format!(
+        "{y:04}{m:02}{day:02}T{:02}{:02}{:02}.{:09}Z",
+        rem / 3600,
+        rem / 60 % 60,
+        rem % 60,
+        d.subsec_nanos()
+    )
+}
+
+fn is_timestamp(s: &str) -> bool {
+    let b = s.as_bytes();
+    b.len() == 26
+        && b[8] == b'T'
+        && b[15] == b'.'
+        && b[25] == b'Z'
+        && b.iter()
+            .enumerate()
+            .all(|(i, c)| matches!(i, 8 | 15 | 25) || c.is_ascii_digit())
+}
+
+/// Days since 1970-01-01 → (year, month, day) in the proleptic Gregorian calendar.
+fn civil_from_days(z: i64) -> (i64, u32, u32) {
+    let z = z + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    (y, m, d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn timestamp_format() {
+        let t = UNIX_EPOCH + Duration::new(1_745_921_700, 42);
+        let s = timestamp(t);
+        assert_eq!(s, "20250429T101500.000000042Z");
+        assert!(is_timestamp(&s));
+        assert!(!is_timestamp("20250429-101500.000000042Z"));
+    }
+
+    #[test]
+    fn civil_dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+    }
+}
@@ -0,0 +1,118 @@
+//! Marker-delimited managed blocks (`blockinfile` semantics).
+use std::ops::Range;
+
+use crate::error::{Error, Result};
+
+/// How the `BEGIN` / `END` marker lines of a managed block are commented.
+///
+/// The marker lines read `{prefix} BEGIN {id}{suffix}` and
+/// `{prefix} END {id}{suffix}`; a non-empty suffix gets a leading space.
+///
+/// ```
+/// # use file_editor::{CommentStyle, Editor};
+/// let mut ed = Editor::from_string("<root>\n</root>\n");
+/// ed.comment_style(CommentStyle::xml())
+///     .ensure_block("users", "<user/>\n", Default::default())?;
+/// assert!(ed.as_str().contains("<!-- BEGIN users -->\n<user/>\n<!-- END users -->\n"));
+/// # Ok::<(), file_editor::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommentStyle {
+    /// Text before `BEGIN` / `END`, e.g. `#` or `<!--`.
+    pub prefix: String,
+    /// Text after the block id, e.g. `-->`; often empty.
+    pub suffix: String,
+}
+
+impl CommentStyle {
+    /// Style with an arbitrary `prefix` and `suffix`.
+    pub fn new(prefix: impl Into<String>, suffix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            suffix: suffix.into(),
+        }
+    }
+
+    /// `# BEGIN id` (shell, TOML, YAML, INI); the default.
+    pub fn hash() -> Self {
+        Self::new("#", "")
+    }
+
+    /// `// BEGIN id` (Rust, C++, JavaScript).
+    pub fn slashes() -> Self {
+        Self::new("//", "")
+    }
+
+    /// `/* BEGIN id */` (C, CSS).
+    pub fn c() -> Self {
+        Self::new("/*", "*/")
+    }
+
+    /// `<!-- BEGIN id -->` (XML, HTML, Markdown).
+    pub fn xml() -> Self {
+        Self::new("<!--", "-->")
+    }
+
+    /// The marker line for `mark` (`BEGIN` or `END`), without line ending.
+    pub(crate) fn marker(&self, mark: &str, id: &str) -> String {
+        let mut line = format!("{} {mark} {id}", self.prefix);
+        if !self.suffix.is_empty() {
+            line.push(' ');
+            line.push_str(&self.suffix);
+        }
+        line
+    }
+}
+
+impl Default for CommentStyle {
+    fn default() -> Self {
+        Self::hash()
+    }
+}
+
+/// Byte range of the block from the start of its `begin` line to the end of
+/// its `end` line (line ending included), if `begin` occurs as a whole line.
+///
+/// A `begin` line without a matching `end` line is an error: replacing or
+/// removing "the rest of the file" is never what the caller wants.
+pub(crate) fn find(buf: &str, begin: &str, end: &str) -> Result<Option<Range<usize>>> {
+    let mut start = None;
+    let mut pos = 0;
+    for line in buf.split_inclusive('\n') {
+        let text = line.trim_end_matches(['\n', '\r']).trim_end();
+        match start {
+            None if text == begin => start = Some(pos),
+            Some(from) if text == end => return Ok(Some(from..pos + line.len())),
+            _ => {}
+        }
+        pos += line.len();
+    }
+    match start {
+        None => Ok(None),
+        Some(_) => Err(Error::validation(format!(
+            "managed block has {begin:?} but no {end:?} line"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marker_lines() {
+        assert_eq!(CommentStyle::hash().marker("BEGIN", "x"), "# BEGIN x");
+        assert_eq!(CommentStyle::c().marker("END", "x"), "/* END x */");
+    }
+
+    #[test]
+    fn finds_whole_line_markers_only() {
+        let buf = "a\n# BEGIN x  \r\nbody\n# END x\nb\n";
+        assert_eq!(find(buf, "# BEGIN x", "# END x").unwrap(), Some(2..28));
+        assert_eq!(
+            find("# BEGIN xy\n# END xy\n", "# BEGIN x", "# END x").unwrap(),
+            None
+        );
+        assert!(find("# BEGIN x\nbody\n", "# BEGIN x", "# END x").is_err());
+    }
+}
@@ -0,0 +1,406 @@
+//! Line-based unified diffs (Myers' O(ND) algorithm in linear space), no
+//! external tools.
+use std::{collections::HashSet, fmt::Write};
+
+/// Layout of [`Editor::diff_with`](crate::Editor::diff_with) output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiffOptions {
+    /// Unchanged lines shown around each change (default 3).
+    pub context: usize,
+    /// Wrap headers, removals and additions in ANSI color escapes.
+    pub color: bool,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            context: 3,
+            color: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Equal,
+    Delete,
+    Insert,
+}
+
+const BOLD: &str = "\x1b[1m";
+const CYAN: &str = "\x1b[36m";
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const RESET: &str = "\x1b[0m";
+
+/// Unified diff from `old` to `new`; empty if they are equal.
+pub(crate) fn unified(
+    old: &str,
+    new: &str,
+    old_label: &str,
+    new_label: &str,
+    opts: &DiffOptions,
+) -> String {
+    let a: Vec<&str> = old.split_inclusive('\n').collect();
+    let b: Vec<&str> = new.split_inclusive('\n').collect();
+    let ops = script(&a, &b);
+    if ops.iter().all(|&op| op == Op::Equal) {
+        return String::new();
+    }
+
+    // position in `a` / `b` before each op
+    let mut pos = Vec::with_capacity(ops.len() + 1);
+    let (mut i, mut j) = (0, 0);
+    for &op in &ops {
+        pos.push((i, j));
+        i += usize::from(op != Op::Insert);
+        j += usize::from(op != Op::Delete);
+    }
+    pos.push((i, j));
+
+    let paint = |color: &'static str| if opts.color { color } else { "" };
+    let reset = paint(RESET);
+    let mut out = String::new();
+    let _ = writeln!(out, "{}--- {old_label}{reset}", paint(BOLD));
+    let _ = writeln!(out, "{}+++ {new_label}{reset}", paint(BOLD));
+
+    for (start, end) in hunks(&ops, opts.context) {
+        let (a0, b0) = pos[start];
+        let (a1, b1) = pos[end];
+        let _ = writeln!(
+            out,
+            "{}@@ -{} +{} @@{reset}",
+            paint(CYAN),
+            range(a0, a1 - a0),
+            range(b0, b1 - b0),
+        );
+        for k in start..end {
+            let (i, j) = pos[k];
+            let (sign, line, color) = match ops[k] {
+                Op::Equal => (' ', a[i], ""),
+                Op::Delete => ('-', a[i], paint(RED)),
+                Op::Insert => ('+', b[j], paint(GREEN)),
+            };
+            let body = line.strip_suffix('\n').unwrap_or(line);
+            let end = if color.is_empty() { "" } else { reset };
+            let _ = writeln!(out, "{color}{sign}{body}{end}");
+            if !line.ends_with('\n') {
+                out.push_str("\\ No newline at end of file\n");
+            }
+        }
+    }
+    out
+}
+
+/// `start,len` in hunk-header form (1-based; `,1` omitted like GNU diff).
+fn range(start: usize, len: usize) -> String {
+    match len {
+        0 => format!("{start},0"),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{len}", start + 1),
+    }
+}
+
+/// Group changed ops into `[start, end)` hunks with `context` lines around them.
+fn hunks(ops: &[Op], context: usize) -> Vec<(usize, usize)> {
+    let mut out: Vec<(usize, usize)> = Vec::new();
+    let mut k = 0;
+    while k < ops.len() {
+        if ops[k] == Op::Equal {
+            k += 1;
+            continue;
+        }
+        let mut end = k;
+        while end < ops.len() && ops[end] != Op::Equal {
+            end += 1;
+        }
+        let start = k.saturating_sub(context);
+        let stop = (end + context).min(ops.len());
+        match out.last_mut() {
+            // overlapping or adjacent context: extend the previous hunk
+            Some(last) if start <= last.1 => last.1 = stop,
+            _ => out.push((start, stop)),
+        }
+        k = end;
+    }
+    out
+}
+
+/// For every line of `a`, the index of the line of `b` it is kept as
+/// (`None` if the shortest edit script deletes it).
+pub(crate) fn matching(a: &[&str], b: &[&str]) -> Vec<Option<usize>> {
+    let mut out = Vec::with_capacity(a.len());
+    let mut j = 0;
+    for op in script(a, b) {
+        match op {
+            Op::Equal => {
+                out.push(Some(j));
+                j += 1;
+            }
+            Op::Delete => out.push(None),
+            Op::Insert => j += 1,
+        }
+    }
+    out
+}
+
+/// Shortest edit script turning `a` into `b`.
+fn script(a: &[&str], b: &[&str]) -> Vec<Op> {
+    // A line found on one side only can never be kept, so it is left out of
+    // the search and deleted or inserted in place afterwards. This makes
+    // rewrites (every line changed) cheap without making the script longer.
+    let in_a: HashSet<&str> = a.iter().copied().collect();
+    let in_b: HashSet<&str> = b.iter().copied().collect();
+    let ka: Vec<usize> = (0..a.len()).filter(|&i| in_b.contains(a[i])).collect();
+    let kb: Vec<usize> = (0..b.len()).filter(|&j| in_a.contains(b[j])).collect();
+    let fa: Vec<&str> = ka.iter().map(|&i| a[i]).collect();
+    let fb: Vec<&str> = kb.iter().map(|&j| b[j]).collect();
+    let mut kept = Vec::with_capacity(fa.len().max(fb.len()));
+    diff_into(&fa, &fb, &mut kept);
+
+    // every change between two kept lines becomes deletes, then inserts
+    let mut ops = Vec::with_capacity(a.len() + b.len());
+    let (mut i, mut j) = (0, 0);
+    let (mut fi, mut fj) = (0, 0);
+    for op in kept {
+        match op {
+            Op::Equal => {
+                let (ti, tj) = (ka[fi], kb[fj]);
+                ops.extend(std::iter::repeat_n(Op::Delete, ti - i));
+                ops.extend(std::iter::repeat_n(Op::Insert, tj - j));
+                ops.push(Op::Equal);
+                (i, j) = (ti + 1, tj + 1);
+                (fi, fj) = (fi + 1, fj + 1);
+            }
+            Op::Delete => fi += 1,
+            Op::Insert => fj += 1,
+        }
+    }
+    ops.extend(std::iter::repeat_n(Op::Delete, a.len() - i));
+    ops.extend(std::iter::repeat_n(Op::Insert, b.len() - j));
+    ops
+}
+
+/// Append the shortest edit script from `a` to `b` to `ops`.
+fn diff_into(a: &[&str], b: &[&str], ops: &mut Vec<Op>) {
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (a_mid, b_mid) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+
+    ops.extend(std::iter::repeat_n(Op::Equal, prefix));
+    match middle(a_mid, b_mid) {
+        Some((x, y)) => {
+            diff_into(&a_mid[..x], &b_mid[..y], ops);
+            diff_into(&a_mid[x..], &b_mid[y..], ops);
+        }
+        None => {
+            ops.extend(std::iter::repeat_n(Op::Delete, a_mid.len()));
+            ops.extend(std::iter::repeat_n(Op::Insert, b_mid.len()));
+        }
+    }
+    ops.extend(std::iter::repeat_n(Op::Equal, suffix));
+}
+
+/// A point `(x, y)` on a shortest path from `(0, 0)` to `(a.len(), b.len())`
+/// that splits it into two halves of about equal cost, found by running
+/// Myers' search forward and backward until the two meet (linear space).
+///
+/// `a` and `b` must differ in their first and last lines, so the point is
+/// neither end and both halves are smaller problems. `None` if they have no
+/// line in common (or either is empty): delete everything, insert everything.
+fn middle(a: &[&str], b: &[&str]) -> Option<(usize, usize)> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max_d = (n + m + 1) / 2;
+    let offset = max_d;
+    let len = 2 * max_d as usize + 2;
+    // furthest x on each diagonal, forward from the start and (as distance
+    // from the end) backward from the end; -1 = not reached yet
+    let mut vf = vec![-1isize; len];
+    let mut vb = vec![-1isize; len];
+    vf[offset as usize + 1] = 0;
+    vb[offset as usize + 1] = 0;
+    let delta = n - m;
+    // with an odd delta the paths meet in a forward step, else a backward one
+    let front = delta % 2 != 0;
+    // diagonals that left the grid are not searched again
+    let (mut kf_start, mut kf_end, mut kb_start, mut kb_end) = (0, 0, 0, 0);
+
+    for d in 0..max_d {
+        let mut k = -d + kf_start;
+        while k <= d - kf_end {
+            let i = (offset + k) as usize;
+            let mut x = if k == -d || (k != d && vf[i - 1] < vf[i + 1]) {
+                vf[i + 1]
+            } else {
+                vf[i - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            vf[i] = x;
+            if x > n {
+                kf_end += 2;
+            } else if y > m {
+                kf_start += 2;
+            } else if front {
+                let reached = reach(&vb, offset + delta - k);
+                if reached.is_some_and(|bx| x >= n - bx) {
+                    return Some((x as usize, y as usize));
+                }
+            }
+            k += 2;
+        }
+
+        let mut k = -d + kb_start;
+        while k <= d - kb_end {
+            let i = (offset + k) as usize;
+            let mut x = if k == -d || (k != d && vb[i - 1] < vb[i + 1]) {
+                vb[i + 1]
+            } else {
+                vb[i - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[(n - x - 1) as usize] == b[(m - y - 1) as usize] {
+                x += 1;
+                y += 1;
+            }
+            vb[i] = x;
+            if x > n {
+                kb_end += 2;
+            } else if y > m {
+                kb_start += 2;
+            } else if !front {
+                let kf = delta - k;
+                if let Some(fx) = reach(&vf, offset + kf).filter(|&fx| fx >= n - x) {
+                    return Some((fx as usize, (fx - kf) as usize));
+                }
+            }
+            k += 2;
+        }
+    }
+    None
+}
+
+/// The x reached on the diagonal at index `i` of `v`, if any.
+fn reach(v: &[isize], i: isize) -> Option<isize> {
+    let x = *v.get(usize::try_from(i).ok()?)?;
+    (x != -1).then_some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diff(old: &str, new: &str) -> String {
+        unified(old, new, "a/f", "b/f", &DiffOptions::default())
+    }
+
+    #[test]
+    fn equal_inputs_give_empty_diff() {
+        assert_eq!(diff("a\nb\n", "a\nb\n"), "");
+    }
+
+    #[test]
+    fn single_change_with_context() {
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n";
+        let new = "1\n2\n3\n4\nfive\n6\n7\n8\n9\n";
+        assert_eq!(
+            diff(old, new),
+            "--- a/f\n+++ b/f\n@@ -2,7 +2,7 @@\n 2\n 3\n 4\n-5\n+five\n 6\n 7\n 8\n"
+        );
+    }
+
+    #[test]
+    fn distant_changes_make_separate_hunks() {
+        let old: String = (1..=20).map(|i| format!("{i}\n")).collect();
+        let new: String = (1..=20)
+            .map(|i| match i {
+                2 => "two\n".to_owned(),
+                19 => "nineteen\n".to_owned(),
+                _ => format!("{i}\n"),
+            })
+            .collect();
+        let out = unified(
+            &old,
+            &new,
+            "a",
+            "b",
+            &DiffOptions {
+                context: 1,
+                color: false,
+            },
+        );
+        assert_eq!(out.matches("@@ -").count(), 2);
+        assert!(out.contains("@@ -1,3 +1,3 @@\n 1\n-2\n+two\n 3\n"));
+    }
+
+    #[test]
+    fn missing_final_newline_is_marked() {
+        assert_eq!(
+            diff("a\nb", "a\nb\n"),
+            "--- a/f\n+++ b/f\n@@ -1,2 +1,2 @@\n a\n-b\n\\ No newline at end of file\n+b\n"
+        );
+        assert_eq!(diff("", "x\n"), "--- a/f\n+++ b/f\n@@ -0,0 +1 @@\n+x\n");
+    }
+
+    /// Length of the longest common subsequence, by dynamic programming.
+    fn lcs(a: &[&str], b: &[&str]) -> usize {
+        let mut row = vec![0; b.len() + 1];
+        for x in a {
+            let mut diag = 0;
+            for (j, y) in b.iter().enumerate() {
+                let up = row[j + 1];
+                row[j + 1] = if x == y { diag + 1 } else { up.max(row[j]) };
+                diag = up;
+            }
+        }
+        row[b.len()]
+    }
+
+    #[test]
+    fn script_is_minimal_and_reproduces_the_target() {
+        let lines = ["a\n", "b\n", "c\n", "d\n"];
+        let mut seed = 0x2545_f491_u32;
+        let mut next = |modulo: u32| {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            (seed % modulo) as usize
+        };
+        for _ in 0..500 {
+            let a: Vec<&str> = (0..next(12)).map(|_| lines[next(4)]).collect();
+            let b: Vec<&str> = (0..next(12)).map(|_| lines[next(4)]).collect();
+            let ops = script(&a, &b);
+            let kept = ops.iter().filter(|&&o| o == Op::Equal).count();
+            assert_eq!(kept, lcs(&a, &b), "{a:?} -> {b:?}");
+
+            let (mut i, mut out) = (0, Vec::new());
+            for op in ops {
+                match op {
+                    Op::Equal => {
+                        out.push(a[i]);
+                        i += 1;
+                    }
+                    Op::Delete => i += 1,
+                    Op::Insert => out.push(b[out.len()]),
+                }
+            }
+            assert_eq!((i, out), (a.len(), b.clone()));
+        }
+    }
+
+    #[test]
+    fn script_is_minimal() {
+        let a = ["a\n", "b\n", "c\n", "a\n", "b\n", "b\n", "a\n"];
+        let b = ["c\n", "b\n", "a\n", "b\n", "a\n", "c\n"];
+        let ops = script(&a, &b);
+        assert_eq!(ops.iter().filter(|&&o| o != Op::Equal).count(), 5);
+    }
+}
@@ -0,0 +1,96 @@
+//! Single-pass splicing of byte-range edits into the buffer.
+use std::ops::Range;
+
+use crate::history::Splice;
+use crate::report::{Change, EditReport};
+
+/// Replace `range` of the buffer with `text`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Edit {
+    pub range: Range<usize>,
+    pub text: String,
+}
+
+impl Edit {
+    pub fn new(range: Range<usize>, text: impl Into<String>) -> Self {
+        Self {
+            range,
+            text: text.into(),
+        }
+    }
+
+    pub fn insert(at: usize, text: impl Into<String>) -> Self {
+        Self::new(at..at, text)
+    }
+}
+
+/// Apply `edits` (sorted, non-overlapping) to `buf`, describe the result and
+/// return the splices needed to undo it.
+pub(crate) fn apply(
+    buf: &mut String,
+    op: &'static str,
+    edits: Vec<Edit>,
+) -> (EditReport, Vec<Splice>) {
+    let mut out = String::with_capacity(buf.len());
+    let mut ranges = Vec::with_capacity(edits.len());
+    let mut splices = Vec::new();
+    let mut last = 0;
+    for edit in edits {
+        debug_assert!(last <= edit.range.start, "edits overlap or are unsorted");
+        out.push_str(&buf[last..edit.range.start]);
+        let start = out.len();
+        out.push_str(&edit.text);
+        ranges.push(start..out.len());
+        let old = &buf[edit.range.clone()];
+        if *old != edit.text {
+            splices.push(Splice {
+                at: edit.range.start,
+                old: old.to_owned(),
+                new: edit.text,
+            });
+        }
+        last = edit.range.end;
+    }
+    out.push_str(&buf[last..]);
+    let changed = !splices.is_empty();
+    if changed {
+        *buf = out;
+    }
+
+    let report = EditReport {
+        op,
+        changes: changes(buf, ranges),
+        changed,
+    };
+    (report, splices)
+}
+
+/// Attach 1-based line spans to byte ranges of `buf` (ranges are ascending).
+fn changes(buf: &str, ranges: Vec<Range<usize>>) -> Vec<Change> {
+    let mut line = 1;
+    let mut pos = 0;
+    let mut line_at = |to: usize| {
+        line += buf.as_bytes()[pos..to]
+            .iter()
+            .filter(|&&b| b == b'\n')
+            .count();
+        pos = to;
+        line
+    };
+    ranges
+        .into_iter()
+        .map(|bytes| {
+            let first = line_at(bytes.start);
+            // a trailing line break belongs to the line it ends
+            let last = if bytes.is_empty() {
+                first
+            } else {
+                line_at(bytes.end - 1)
+            };
+            Change {
+                lines: first..=last,
+                bytes,
+            }
+        })
+        .collect()
+}
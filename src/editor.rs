@@ -1,12 +1,49 @@
 use std::{
-    fs, io,
-    path::{Path, PathBuf},
+    borrow::Cow,
+    fs,
+    io::{Read, Write},
+    path::{Component, Path, PathBuf},
 };
 
+use crate::atomic;
+use crate::backup::{self, Backup};
+use crate::block::CommentStyle;
+use crate::diff::{self, DiffOptions};
+use crate::edit::{self, Edit};
+use crate::encoding::Encoding;
+use crate::error::{Context, Error, Result};
+use crate::expect::Expect;
+use crate::history::{History, Splice};
+use crate::journal::Journal;
+use crate::line_ending::{LineEnding, LineEndingPolicy};
+use crate::lock::{Guard, Held};
+use crate::marker::Marker;
+use crate::merge::MergeStrategy;
+use crate::metadata::{self, Preserve};
+use crate::normalize::{self, Normalize, NormalizeReport};
 use crate::pattern::Pattern;
+use crate::report::{EditReport, SaveReport};
+use crate::stamp::FileStamp;
 use crate::utils::line_indent;
 
-/// Handle to a UTF-8 text file kept in memory until [`save`](Editor::save) is called.
+mod block;
+mod checkpoint;
+mod ensure;
+mod journal;
+mod lines;
+mod merge;
+mod options;
+mod patch;
+mod position;
+mod scope;
+pub use options::EditorOptions;
+pub use scope::Scope;
+
+/// Handle to a text file kept in memory until [`save`](Editor::save) is called.
+///
+/// An editor may also be detached from the filesystem
+/// ([`from_string`](Editor::from_string), [`from_reader`](Editor::from_reader));
+/// such an editor has no [`path`](Editor::path) until [`save_as`](Editor::save_as).
 ///
 /// All mutating methods return `&mut self`, enabling a fluent builder style.
 ///
@@ -20,78 +57,626 @@ use crate::utils::line_indent;
 /// ```
 #[derive(Debug, Clone)]
 pub struct Editor {
-    path: PathBuf,
+    path: Option<PathBuf>,
     buf: String,
+    /// Contents as last read from or written to disk, for [`Editor::diff`].
+    base: String,
     dirty: bool,
+    fsync: bool,
+    dry_run: bool,
+    stamp: Option<FileStamp>,
+    backup: Backup,
+    eol: LineEnding,
+    eol_policy: LineEndingPolicy,
+    encoding: Encoding,
+    bom: bool,
+    create_dirs: bool,
+    mode: Option<u32>,
+    preserve: Preserve,
+    lock: Held,
+    normalize: Normalize,
+    merge: MergeStrategy,
+    comment: CommentStyle,
+    last_save: Option<SaveReport>,
+    reports: Vec<EditReport>,
+    history: History,
+    checkpoints: Vec<checkpoint::Checkpoint>,
+    journal: Option<Journal>,
 }
 
 impl Editor {
     /// **Create** or truncate a file and return an editor over it.
     ///
     /// Equivalent to `fs::write(path, "")` followed by [`open`](Editor::open).
-    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
-        fs::write(&path, "")?;
-        Self::open(path)
+    /// See [`create_new`](Editor::create_new) for a variant that never clobbers.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::options().create(true).truncate(true).open(path)
+    }
+
+    /// **Create** a new file, failing with `AlreadyExists` if it is already there.
+    pub fn create_new<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::options().create_new(true).open(path)
+    }
+
+    /// **Open** a file, creating an empty one first if it is missing.
+    pub fn open_or_create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::options().create(true).open(path)
+    }
+
+    /// **Open** `path` with a set of [`EditorOptions`].
+    pub fn open_with<P: AsRef<Path>>(path: P, options: &EditorOptions) -> Result<Self> {
+        options.open(path)
     }
 
-    /// **Open** an existing UTF-8 file into an in-memory buffer.
-    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
-        let p = path.as_ref().to_owned();
-        let buf = fs::read_to_string(&p)?;
-        Ok(Self {
-            path: p,
+    /// Blank [`EditorOptions`], for `Editor::options().create(true).open(..)`.
+    pub fn options() -> EditorOptions {
+        EditorOptions::new()
+    }
+
+    /// **Open** an existing text file into an in-memory buffer.
+    ///
+    /// The encoding is sniffed from the byte-order mark (UTF-8, UTF-16LE/BE;
+    /// UTF-8 without one) and the BOM is kept out of the buffer.
+    /// The file's identity (mtime, size, content hash) is recorded so that
+    /// [`save`](Editor::save) can detect concurrent modifications, and its
+    /// dominant [`LineEnding`] is detected for inserted text.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::load(path.as_ref(), None)
+    }
+
+    /// **Open** a file in an explicit [`Encoding`] instead of sniffing it.
+    ///
+    /// UTF-16 without a BOM cannot be sniffed, and legacy code pages such as
+    /// `Encoding::Windows1252` (feature `legacy-encodings`) never can.
+    ///
+    /// ```no_run
+    /// # use file_editor::{Editor, Encoding};
+    /// # fn run() -> std::io::Result<()> {
+    /// Editor::open_with_encoding("strings.txt", Encoding::Utf16Le)?
+    ///     .replace("colour", "color")
+    ///     .save()?; // written back as UTF-16LE
+    /// # Ok(()) }
+    /// ```
+    pub fn open_with_encoding<P: AsRef<Path>>(path: P, encoding: Encoding) -> Result<Self> {
+        Self::load(path.as_ref(), Some(encoding))
+    }
+
+    fn load(path: &Path, encoding: Option<Encoding>) -> Result<Self> {
+        let file = read(path, encoding)?;
+        let mut ed = Self::from_string(file.buf);
+        ed.path = Some(path.to_owned());
+        ed.stamp = Some(file.stamp);
+        ed.encoding = file.encoding;
+        ed.bom = file.bom;
+        Ok(ed)
+    }
+
+    /// Build an **in-memory** editor over `text`; nothing touches the disk.
+    ///
+    /// ```
+    /// # use file_editor::Editor;
+    /// let mut ed = Editor::from_string("name = \"demo\"\n");
+    /// ed.replace("demo", "prod");
+    /// assert_eq!(ed.as_str(), "name = \"prod\"\n");
+    /// ```
+    pub fn from_string<S: Into<String>>(text: S) -> Self {
+        let buf = text.into();
+        Self {
+            path: None,
+            eol: LineEnding::detect(&buf),
+            base: buf.clone(),
             buf,
             dirty: false,
-        })
+            fsync: false,
+            dry_run: false,
+            stamp: None,
+            backup: Backup::None,
+            eol_policy: LineEndingPolicy::Keep,
+            encoding: Encoding::Utf8,
+            bom: false,
+            create_dirs: false,
+            mode: None,
+            preserve: Preserve::default(),
+            lock: Held::default(),
+            normalize: Normalize::default(),
+            merge: MergeStrategy::default(),
+            comment: CommentStyle::default(),
+            last_save: None,
+            reports: Vec::new(),
+            history: History::default(),
+            checkpoints: Vec::new(),
+            journal: None,
+        }
+    }
+
+    /// Build an **in-memory** editor from everything `reader` yields.
+    ///
+    /// The encoding is sniffed from the BOM exactly as in [`open`](Editor::open).
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        let (buf, encoding, bom) = Encoding::decode(&bytes, None)?;
+        let mut ed = Self::from_string(buf);
+        ed.encoding = encoding;
+        ed.bom = bom;
+        Ok(ed)
+    }
+
+    /// The current buffer contents.
+    pub fn as_str(&self) -> &str {
+        &self.buf
+    }
+
+    /// Consume the editor and return the buffer.
+    pub fn into_string(self) -> String {
+        self.buf
+    }
+
+    /// File this editor saves to; `None` for in-memory editors.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
     }
 
     /// Rename the underlying file on disk **and** update the internal path.
-    pub fn rename<P: AsRef<Path>>(&mut self, new_name: P) -> io::Result<&mut Self> {
-        fs::rename(&self.path, &new_name)?;
-        self.path = new_name.as_ref().to_owned();
+    ///
+    /// A symlink is renamed itself (not its target); with
+    /// [`Preserve::follow_symlinks`] a relative link moved to another
+    /// directory is re-created so that it still points at the same file.
+    pub fn rename<P: AsRef<Path>>(&mut self, new_name: P) -> Result<&mut Self> {
+        let follow = self.preserve.follow_symlinks;
+        let new_name = new_name.as_ref();
+        metadata::rename(self.require_path()?, new_name, follow).at(new_name)?;
+        self.path = Some(new_name.to_owned());
         Ok(self)
     }
 
-    /// Write the in-memory buffer back to disk **iff** it was modified.
+    /// Write the in-memory buffer back to disk **iff** it was modified, or
+    /// the [`normalize`](Editor::normalize) / line-ending policy would change it.
+    ///
+    /// The write is atomic: the buffer goes to a temp file next to the target,
+    /// which is then renamed over it. A crash mid-save leaves the old file intact.
+    ///
+    /// Fails with [`Error::Conflict`] if the file's contents changed on disk
+    /// since it was opened (or last saved / reloaded); nothing is written then.
+    /// Resolve with [`save_force`](Editor::save_force) or [`reload`](Editor::reload),
+    /// or pick a [`merge_strategy`](Editor::merge_strategy) to merge the
+    /// external changes instead.
     ///
-    /// Returns `Ok(self)` even when there was nothing to do.
-    pub fn save(&mut self) -> io::Result<&mut Self> {
+    /// Returns `Ok(self)` even when there was nothing to do, and
+    /// [`Error::Validation`] for in-memory editors.
+    pub fn save(&mut self) -> Result<&mut Self> {
+        let path = self.require_path()?.to_owned();
+        self.dirty |= self.policies_pending();
         if self.dirty {
-            fs::write(&self.path, &self.buf)?;
-            self.dirty = false;
+            let on_disk = FileStamp::read(&path).at(&path)?;
+            let unchanged = match (&on_disk, &self.stamp) {
+                (Some(now), Some(then)) => now.same_contents(then),
+                (None, None) => true,
+                _ => false,
+            };
+            if !unchanged {
+                if on_disk.is_none() || self.merge == MergeStrategy::Refuse {
+                    return Err(Error::Conflict { path });
+                }
+                self.merge_from_disk(&path)?;
+            }
+            self.write(&path)?;
+            if let Some(report) = &mut self.last_save {
+                report.merged = !unchanged;
+            }
+        } else {
+            self.last_save = Some(SaveReport {
+                path: Some(path),
+                ..SaveReport::default()
+            });
+        }
+        Ok(self)
+    }
+
+    /// Write the buffer **unconditionally**, overwriting any changes made on
+    /// disk since the file was opened.
+    pub fn save_force(&mut self) -> Result<&mut Self> {
+        let path = self.require_path()?.to_owned();
+        self.write(&path)?;
+        Ok(self)
+    }
+
+    /// Write the buffer to `path` and make it this editor's file from now on.
+    ///
+    /// Works for in-memory editors too; an existing file at `path` is
+    /// overwritten (after a backup, if a [`Backup`] policy is set).
+    ///
+    /// Saving to a different path releases any lock held on the old file.
+    /// In [`dry_run`](Editor::dry_run) mode nothing is written and the editor
+    /// keeps its current path.
+    pub fn save_as<P: AsRef<Path>>(&mut self, path: P) -> Result<&mut Self> {
+        let path = path.as_ref().to_owned();
+        if self.dry_run {
+            self.write(&path)?;
+            return Ok(self);
+        }
+        if self.path.as_ref() != Some(&path) {
+            self.unlock();
+        }
+        self.write(&path)?;
+        self.path = Some(path);
+        Ok(self)
+    }
+
+    /// Write the encoded buffer to `writer` (no file, no backup, no dirty reset).
+    ///
+    /// ```
+    /// # use file_editor::Editor;
+    /// let mut out = Vec::new();
+    /// Editor::from_string("a\n").append("b\n").save_to(&mut out)?;
+    /// assert_eq!(out, b"a\nb\n");
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn save_to<W: Write>(&mut self, mut writer: W) -> Result<&mut Self> {
+        let (bytes, normalized) = self.prepare()?;
+        writer.write_all(&bytes)?;
+        writer.flush()?;
+        self.last_save = Some(SaveReport {
+            written: true,
+            path: None,
+            bytes: bytes.len(),
+            backup: None,
+            normalized,
+            merged: false,
+        });
+        Ok(self)
+    }
+
+    /// Discard the in-memory buffer and re-read the file from disk.
+    ///
+    /// Clears the undo history.
+    pub fn reload(&mut self) -> Result<&mut Self> {
+        let file = read(self.require_path()?, Some(self.encoding))?;
+        self.eol = LineEnding::detect(&file.buf);
+        self.base.clone_from(&file.buf);
+        self.buf = file.buf;
+        self.stamp = Some(file.stamp);
+        self.bom = file.bom;
+        self.dirty = false;
+        self.reports.clear();
+        self.history.clear();
+        Ok(self)
+    }
+
+    /// Identity of the file as last seen by this editor (open, save or reload).
+    pub fn stamp(&self) -> Option<&FileStamp> {
+        self.stamp.as_ref()
+    }
+
+    /// Keep the previous contents whenever a save overwrites the file.
+    ///
+    /// ```no_run
+    /// # use file_editor::{Backup, Editor};
+    /// # fn run() -> std::io::Result<()> {
+    /// Editor::open("app.toml")?
+    ///     .backup(Backup::Numbered { keep: 5 }) // app.toml.~1~ … app.toml.~5~
+    ///     .replace("debug = true", "debug = false")
+    ///     .save()?;
+    /// # Ok(()) }
+    /// ```
+    pub fn backup(&mut self, policy: Backup) -> &mut Self {
+        self.backup = policy;
+        self
+    }
+
+    /// Backups of this file under the current policy, **oldest first**.
+    pub fn backups(&self) -> Result<Vec<PathBuf>> {
+        match &self.path {
+            Some(path) => backup::list(&self.backup, path).at(path),
+            None => Ok(Vec::new()),
         }
+    }
+
+    /// Replace the buffer with the contents of `backup`.
+    ///
+    /// Nothing touches the file until the next [`save`](Editor::save), which
+    /// itself backs up the version being replaced.
+    pub fn restore_backup<P: AsRef<Path>>(&mut self, backup: P) -> Result<&mut Self> {
+        let text = read(backup.as_ref(), Some(self.encoding))?.buf;
+        self.rewrite("restore_backup", text);
         Ok(self)
     }
 
+    /// Line ending used for inserted text (detected on open).
+    pub fn line_ending(&self) -> LineEnding {
+        self.eol
+    }
+
+    /// Choose what happens to line endings on save.
+    ///
+    /// [`Force`](LineEndingPolicy::Force) converts the buffer right away (marking
+    /// it dirty if anything changed) and again on every save; text inserted
+    /// afterwards uses the forced style.
+    pub fn line_ending_policy(&mut self, policy: LineEndingPolicy) -> &mut Self {
+        self.eol_policy = policy;
+        if let LineEndingPolicy::Force(eol) = policy {
+            self.eol = eol;
+            self.normalize_eol();
+        }
+        self
+    }
+
+    fn normalize_eol(&mut self) {
+        let LineEndingPolicy::Force(eol) = self.eol_policy else {
+            return;
+        };
+        if let Cow::Owned(converted) = eol.apply(&self.buf) {
+            self.rewrite("line_ending", converted);
+        }
+    }
+
+    /// Replace the whole buffer with `text`, as one undoable step.
+    fn rewrite(&mut self, name: &'static str, text: String) {
+        if let Some(splice) = Splice::between(&self.buf, &text) {
+            self.buf = text;
+            if self.journal.is_some() {
+                self.log(Op::new(name, None, None), Vec::new(), vec![splice.clone()]);
+            }
+            self.history.record(vec![splice], self.dirty);
+            self.dirty = true;
+        }
+    }
+
+    /// Encoding used to read the file and to save it.
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// `true` if the file started with a byte-order mark (kept on save).
+    pub fn has_bom(&self) -> bool {
+        self.bom
+    }
+
+    /// Save in a different `encoding`, with or without a BOM.
+    pub fn transcode(&mut self, encoding: Encoding, bom: bool) -> &mut Self {
+        self.dirty |= encoding != self.encoding || bom != self.bom;
+        self.encoding = encoding;
+        self.bom = bom;
+        self
+    }
+
+    /// Whitespace clean-up applied to the buffer on every save.
+    ///
+    /// ```no_run
+    /// # use file_editor::{Editor, Normalize};
+    /// # fn run() -> std::io::Result<()> {
+    /// let mut ed = Editor::open("generated.rs")?;
+    /// ed.normalize(Normalize::editorconfig())
+    ///     .append("\n\n// end   \n\n")
+    ///     .save()?;
+    /// let report = &ed.last_save().unwrap().normalized;
+    /// println!("trimmed {} lines", report.trimmed_lines);
+    /// # Ok(()) }
+    /// ```
+    pub fn normalize(&mut self, policy: Normalize) -> &mut Self {
+        self.normalize = policy;
+        self
+    }
+
+    /// What the most recent save did; `None` before the first save.
+    pub fn last_save(&self) -> Option<&SaveReport> {
+        self.last_save.as_ref()
+    }
+
+    /// `true` if the line-ending or [`Normalize`] policy would change the buffer.
+    fn policies_pending(&self) -> bool {
+        let eol = match self.eol_policy {
+            LineEndingPolicy::Force(eol) => matches!(eol.apply(&self.buf), Cow::Owned(_)),
+            _ => false,
+        };
+        let (_, report) = normalize::apply(&self.buf, &self.normalize, self.eol);
+        eol || report.changed()
+    }
+
+    /// Apply save-time policies to the buffer and encode it.
+    fn prepare(&mut self) -> Result<(Vec<u8>, NormalizeReport)> {
+        self.normalize_eol();
+        let (text, report) = normalize::apply(&self.buf, &self.normalize, self.eol);
+        if report.changed() {
+            self.rewrite("normalize", text);
+        }
+        Ok((self.encoding.encode(&self.buf, self.bom)?, report))
+    }
+
+    fn write(&mut self, path: &Path) -> Result<()> {
+        if self.dry_run {
+            let (bytes, normalized) = self.prepare()?;
+            self.last_save = Some(SaveReport {
+                written: false,
+                path: Some(path.to_owned()),
+                bytes: bytes.len(),
+                backup: None,
+                normalized,
+                merged: false,
+            });
+            return Ok(());
+        }
+        self.write_inner(path).at(path)
+    }
+
+    fn write_inner(&mut self, path: &Path) -> Result<()> {
+        let (bytes, normalized) = self.prepare()?;
+        let backup = backup::create(&self.backup, path)?;
+        let target = if self.preserve.follow_symlinks {
+            metadata::resolve(path)?
+        } else {
+            path.to_owned()
+        };
+        if self.create_dirs && !target.try_exists()? {
+            options::create_parent(&target)?;
+        }
+        let opts = atomic::Options {
+            fsync: self.fsync,
+            mode: self.mode,
+            preserve: self.preserve,
+            lock: matches!(self.lock.0, Some(Guard::Advisory(_))),
+        };
+        if let Some(file) = atomic::write(&target, &bytes, &opts)? {
+            self.lock.0 = Some(Guard::Advisory(file));
+        }
+        self.stamp = Some(FileStamp::new(&fs::metadata(path)?, &bytes));
+        self.base.clone_from(&self.buf);
+        self.dirty = false;
+        self.reports.clear();
+        self.history.saved();
+        self.last_save = Some(SaveReport {
+            written: true,
+            path: Some(path.to_owned()),
+            bytes: bytes.len(),
+            backup,
+            normalized,
+            merged: false,
+        });
+        Ok(())
+    }
+
+    fn require_path(&self) -> Result<&Path> {
+        self.path.as_deref().ok_or_else(|| {
+            Error::validation("in-memory editor has no path; use `save_as` or `save_to`")
+        })
+    }
+
+    /// Choose which metadata of the original file a save keeps
+    /// (everything by default, see [`Preserve`]).
+    ///
+    /// ```no_run
+    /// # use file_editor::{Editor, Preserve};
+    /// # fn run() -> std::io::Result<()> {
+    /// Editor::open("/etc/hosts")?
+    ///     .preserve(Preserve { ownership: false, ..Preserve::default() })
+    ///     .append("10.0.0.2 db\n")
+    ///     .save()?;
+    /// # Ok(()) }
+    /// ```
+    pub fn preserve(&mut self, preserve: Preserve) -> &mut Self {
+        self.preserve = preserve;
+        self
+    }
+
+    /// `true` while this editor holds a lock on its file
+    /// (see [`EditorOptions::lock`]).
+    pub fn is_locked(&self) -> bool {
+        self.lock.0.is_some()
+    }
+
+    /// Release the lock early; it is otherwise held until the editor is dropped.
+    pub fn unlock(&mut self) -> &mut Self {
+        self.lock.0 = None;
+        self
+    }
+
+    /// Opt in to **durable** saves: `fsync` the new file and its parent
+    /// directory so the rename survives power loss (off by default).
+    pub fn fsync(&mut self, on: bool) -> &mut Self {
+        self.fsync = on;
+        self
+    }
+
+    /// Turn **dry-run** mode on or off.
+    ///
+    /// While on, [`save`](Editor::save), [`save_force`](Editor::save_force) and
+    /// [`save_as`](Editor::save_as) touch nothing on disk: they only apply the
+    /// save-time policies to the buffer and fill in [`last_save`](Editor::last_save)
+    /// (with `written: false`). The buffer stays dirty; inspect it with
+    /// [`diff`](Editor::diff).
+    ///
+    /// ```no_run
+    /// # use file_editor::Editor;
+    /// # fn run() -> std::io::Result<()> {
+    /// let mut ed = Editor::open("Cargo.toml")?;
+    /// ed.dry_run(true).replace("edition = \"2021\"", "edition = \"2024\"").save()?;
+    /// print!("{}", ed.diff()); // review; the file is unchanged
+    /// # Ok(()) }
+    /// ```
+    pub fn dry_run(&mut self, on: bool) -> &mut Self {
+        self.dry_run = on;
+        self
+    }
+
+    /// `true` while in [`dry_run`](Editor::dry_run) mode.
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Unified diff from the contents on disk (as of open, reload or the last
+    /// save) to the current buffer, with 3 lines of context and no color.
+    ///
+    /// The output is accepted by `git apply` / `patch -p1` when run from the
+    /// directory the editor's path is relative to. Empty when nothing changed.
+    ///
+    /// ```
+    /// # use file_editor::Editor;
+    /// let mut ed = Editor::from_string("a\nb\n");
+    /// ed.replace("b", "c");
+    /// assert_eq!(ed.diff(), "--- a/buffer\n+++ b/buffer\n@@ -1,2 +1,2 @@\n a\n-b\n+c\n");
+    /// ```
+    pub fn diff(&self) -> String {
+        self.diff_with(&DiffOptions::default())
+    }
+
+    /// [`diff`](Editor::diff) with explicit [`DiffOptions`].
+    ///
+    /// Headers name the file `a/<path>` and `b/<path>` (`/dev/null` for a file
+    /// not yet created; `buffer` for in-memory editors).
+    pub fn diff_with(&self, opts: &DiffOptions) -> String {
+        let name = match &self.path {
+            Some(path) => header_path(path),
+            None => "buffer".to_owned(),
+        };
+        let old = if self.path.is_some() && self.stamp.is_none() {
+            "/dev/null".to_owned()
+        } else {
+            format!("a/{name}")
+        };
+        diff::unified(&self.base, &self.buf, &old, &format!("b/{name}"), opts)
+    }
+
     /// Insert `text` **at the beginning** of the buffer.
     pub fn prepend(&mut self, text: &str) -> &mut Self {
-        self.buf.insert_str(0, text);
-        self.dirty = true;
-        self
+        let op = Op::new("prepend", None, Some(text));
+        let text = self.eol.apply(text);
+        self.splice(op, vec![Edit::insert(0, text)])
     }
 
     /// Append `text` **to the end** of the buffer.
     pub fn append(&mut self, text: &str) -> &mut Self {
-        self.buf.push_str(text);
-        self.dirty = true;
-        self
+        let op = Op::new("append", None, Some(text));
+        let text = self.eol.apply(text);
+        let end = self.buf.len();
+        self.splice(op, vec![Edit::insert(end, text)])
     }
 
     /// Insert `text` **before** the first occurrence of `marker`.
     ///
     /// * If `same_indent` is `true`, the current indentation of the line
     ///   containing `marker` is copied and prepended to `text`.
-    pub fn insert_before(&mut self, marker: &str, text: &str, same_indent: bool) -> &mut Self {
-        if let Some(pos) = self.buf.find(marker) {
-            let insertion = if same_indent {
-                format!("{}{}", line_indent(&self.buf, pos), text)
-            } else {
-                text.to_owned()
-            };
-            self.buf.insert_str(pos, &insertion);
-            self.dirty = true;
-        }
-        self
+    /// * Pass `(marker, Occurrence::…)` to act on other or several
+    ///   occurrences (see [`Marker`]).
+    pub fn insert_before<'a, M>(&mut self, marker: M, text: &str, same_indent: bool) -> &mut Self
+    where
+        M: Into<Marker<'a>>,
+    {
+        let marker = marker.into();
+        let op = Op::new("insert_before", Some(marker.text), Some(text));
+        let text = &*self.eol.apply(text);
+        let edits = marker
+            .positions(&self.buf)
+            .into_iter()
+            .map(|pos| {
+                let insertion = if same_indent {
+                    format!("{}{}", line_indent(&self.buf, pos), text)
+                } else {
+                    text.to_owned()
+                };
+                Edit::insert(pos, insertion)
+            })
+            .collect();
+        self.splice(op, edits)
     }
 
     /// Insert `text` **after** the first occurrence of `marker`.
@@ -100,63 +685,90 @@ impl Editor {
     /// * Otherwise the insertion is in-line; a space is auto-inserted when needed.  
     /// * When `same_indent` is `true`, every *subsequent* line in `text`
     ///   is indented to match the marker line.
-    pub fn insert_after(&mut self, marker: &str, text: &str, same_indent: bool) -> &mut Self {
-        if let Some(pos) = self.buf.find(marker) {
-            let after_marker = pos + marker.len();
-            let insert_pos = if self.buf[after_marker..].starts_with('\n') {
-                after_marker + 1 // insert on next line
-            } else {
-                after_marker // insert in-line
-            };
+    /// * Pass `(marker, Occurrence::…)` to act on other or several
+    ///   occurrences (see [`Marker`]).
+    pub fn insert_after<'a, M>(&mut self, marker: M, text: &str, same_indent: bool) -> &mut Self
+    where
+        M: Into<Marker<'a>>,
+    {
+        let marker = marker.into();
+        let op = Op::new("insert_after", Some(marker.text), Some(text));
+        let text = &*self.eol.apply(text);
+        let edits = marker
+            .positions(&self.buf)
+            .into_iter()
+            .map(|pos| self.insertion_after(pos, marker.text.len(), text, same_indent))
+            .collect();
+        self.splice(op, edits)
+    }
 
-            let mut insertion = text.to_owned();
+    /// The edit [`insert_after`](Editor::insert_after) makes for a marker at `pos`.
+    fn insertion_after(&self, pos: usize, len: usize, text: &str, same_indent: bool) -> Edit {
+        let after_marker = pos + len;
+        let rest = &self.buf[after_marker..];
+        let insert_pos = if rest.starts_with("\r\n") {
+            after_marker + 2 // insert on next line (CRLF)
+        } else if rest.starts_with('\n') {
+            after_marker + 1 // insert on next line
+        } else {
+            after_marker // insert in-line
+        };
 
-            // Auto-space for inline insertions like `foo|bar` → `foo X bar`
-            if insert_pos == after_marker
-                && !insertion.starts_with(char::is_whitespace)
-                && !self.buf[insert_pos..].starts_with(char::is_whitespace)
-            {
-                insertion.insert(0, ' ');
-            }
+        let mut insertion = text.to_owned();
 
-            // Re-indent multiline insertions
-            if same_indent && insertion.contains('\n') {
-                let indent = line_indent(&self.buf, pos);
-                insertion = insertion
-                    .split('\n')
-                    .enumerate()
-                    .map(|(i, line)| {
-                        if i == 0 {
-                            line.to_owned()
-                        } else {
-                            format!("{indent}{line}")
-                        }
-                    })
-                    .collect::<Vec<_>>()
-                    .join("\n");
-            }
+        // Auto-space for inline insertions like `foo|bar` → `foo X bar`
+        if insert_pos == after_marker
+            && !insertion.starts_with(char::is_whitespace)
+            && !self.buf[insert_pos..].starts_with(char::is_whitespace)
+        {
+            insertion.insert(0, ' ');
+        }
 
-            self.buf.insert_str(insert_pos, &insertion);
-            self.dirty = true;
+        // Re-indent multiline insertions
+        if same_indent && insertion.contains('\n') {
+            let indent = line_indent(&self.buf, pos);
+            insertion = insertion
+                .split('\n')
+                .enumerate()
+                .map(|(i, line)| {
+                    if i == 0 {
+                        line.to_owned()
+                    } else {
+                        format!("{indent}{line}")
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
         }
-        self
+
+        Edit::insert(insert_pos, insertion)
     }
 
     /// Replace the first occurrence of `marker` with `text`.
     ///
     /// When `same_indent` is `true`, the replacement receives the indentation
-    /// that preceded the marker.
-    pub fn replace_marker(&mut self, marker: &str, text: &str, same_indent: bool) -> &mut Self {
-        if let Some(pos) = self.buf.find(marker) {
-            let indent = if same_indent {
-                line_indent(&self.buf, pos)
-            } else {
-                String::new()
-            };
-            self.buf = self.buf.replacen(marker, &(indent + text), 1);
-            self.dirty = true;
-        }
-        self
+    /// that preceded the marker. Pass `(marker, Occurrence::…)` to replace
+    /// other or several occurrences (see [`Marker`]).
+    pub fn replace_marker<'a, M>(&mut self, marker: M, text: &str, same_indent: bool) -> &mut Self
+    where
+        M: Into<Marker<'a>>,
+    {
+        let marker = marker.into();
+        let op = Op::new("replace_marker", Some(marker.text), Some(text));
+        let text = &*self.eol.apply(text);
+        let edits = marker
+            .positions(&self.buf)
+            .into_iter()
+            .map(|pos| {
+                let indent = if same_indent {
+                    line_indent(&self.buf, pos)
+                } else {
+                    String::new()
+                };
+                Edit::new(pos..pos + marker.text.len(), indent + text)
+            })
+            .collect();
+        self.splice(op, edits)
     }
 
     /// Return 1-based line numbers where `pattern` occurs.
@@ -182,9 +794,8 @@ impl Editor {
         P: Into<Pattern<'a>>,
     {
         let pat = pattern.into();
-        self.buf = pat.replace_all(&self.buf, "");
-        self.dirty = true;
-        self
+        let edits = pat.edits(&self.buf, "");
+        self.splice(Op::new("erase", Some(pat.as_str()), None), edits)
     }
 
     /// Replace _all_ occurrences of `pattern` with `replacement`.
@@ -193,9 +804,10 @@ impl Editor {
         P: Into<Pattern<'a>>,
     {
         let pat = pattern.into();
-        self.buf = pat.replace_all(&self.buf, replacement);
-        self.dirty = true;
-        self
+        let op = Op::new("replace", Some(pat.as_str()), Some(replacement));
+        let replacement = self.eol.apply(replacement);
+        let edits = pat.edits(&self.buf, &replacement);
+        self.splice(op, edits)
     }
 
     /// Mask _all_ occurrences of `pattern` with `mask`.
@@ -203,6 +815,291 @@ impl Editor {
     where
         P: Into<Pattern<'a>>,
     {
-        self.replace(pattern, mask)
+        let pat = pattern.into();
+        let op = Op::new("mask", Some(pat.as_str()), Some(mask));
+        let mask = self.eol.apply(mask);
+        let edits = pat.edits(&self.buf, &mask);
+        self.splice(op, edits)
+    }
+
+    /// What the most recent editing operation did.
+    ///
+    /// ```
+    /// # use file_editor::Editor;
+    /// let mut ed = Editor::from_string("a=1\nb=1\n");
+    /// let report = ed.replace("=1", "=2").last_report().unwrap();
+    /// assert_eq!(report.matches(), 2);
+    /// assert_eq!(report.changes[1].lines, 2..=2);
+    /// ```
+    pub fn last_report(&self) -> Option<&EditReport> {
+        self.reports.last()
+    }
+
+    /// Reports of every editing operation since the editor was opened,
+    /// reloaded or last saved, oldest first.
+    pub fn reports(&self) -> &[EditReport] {
+        &self.reports
+    }
+
+    /// Apply `edits` in one pass, record the report and mark the buffer dirty
+    /// only if its contents changed.
+    fn splice(&mut self, op: Op<'_>, edits: Vec<Edit>) -> &mut Self {
+        let (report, splices) = edit::apply(&mut self.buf, op.name, edits);
+        if self.journal.is_some() {
+            let matches = report.changes.iter().map(|c| c.bytes.clone()).collect();
+            self.log(op, matches, splices.clone());
+        }
+        self.history.record(splices, self.dirty);
+        self.dirty |= report.changed;
+        self.reports.push(report);
+        self
+    }
+
+    /// **Undo** the most recent buffer change; `false` if there is none.
+    ///
+    /// Every editing call is one step, as are line-ending conversions,
+    /// [`restore_backup`](Editor::restore_backup) and save-time
+    /// [`normalize`](Editor::normalize) fixes. Steps are stored as the spans
+    /// they changed, not as copies of the buffer.
+    ///
+    /// ```
+    /// # use file_editor::Editor;
+    /// let mut ed = Editor::from_string("port = 80\n");
+    /// ed.replace("80", "8080").append("host = db\n");
+    /// ed.undo();
+    /// assert_eq!(ed.as_str(), "port = 8080\n");
+    /// ed.undo();
+    /// ed.redo();
+    /// assert_eq!(ed.as_str(), "port = 8080\n");
+    /// ```
+    pub fn undo(&mut self) -> bool {
+        let before = self.journal.is_some().then(|| self.buf.clone());
+        match self.history.undo(&mut self.buf) {
+            Some(was_dirty) => {
+                self.dirty = was_dirty;
+                self.log_change("undo", before);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// **Redo** the most recently undone change; `false` if there is none.
+    ///
+    /// Any new edit after an undo discards what could be redone.
+    pub fn redo(&mut self) -> bool {
+        let before = self.journal.is_some().then(|| self.buf.clone());
+        let redone = self.history.redo(&mut self.buf);
+        self.dirty |= redone;
+        if redone {
+            self.log_change("redo", before);
+        }
+        redone
+    }
+
+    /// `true` if [`undo`](Editor::undo) would do something.
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+
+    /// `true` if [`redo`](Editor::redo) would do something.
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+
+    /// Keep at most `steps` undo steps (default 100); `0` disables history.
+    pub fn history_limit(&mut self, steps: usize) -> &mut Self {
+        self.history.set_limit(steps);
+        self
+    }
+
+    /// Like [`insert_before`](Editor::insert_before), but fails with
+    /// [`Error::NotFound`] instead of silently doing nothing.
+    pub fn try_insert_before<'a, M>(
+        &mut self,
+        marker: M,
+        text: &str,
+        same_indent: bool,
+    ) -> Result<&mut Self>
+    where
+        M: Into<Marker<'a>>,
+    {
+        let marker = marker.into();
+        self.require_marker(&marker)?;
+        Ok(self.insert_before(marker, text, same_indent))
+    }
+
+    /// Like [`insert_after`](Editor::insert_after), but fails with
+    /// [`Error::NotFound`] instead of silently doing nothing.
+    pub fn try_insert_after<'a, M>(
+        &mut self,
+        marker: M,
+        text: &str,
+        same_indent: bool,
+    ) -> Result<&mut Self>
+    where
+        M: Into<Marker<'a>>,
+    {
+        let marker = marker.into();
+        self.require_marker(&marker)?;
+        Ok(self.insert_after(marker, text, same_indent))
+    }
+
+    /// Like [`replace_marker`](Editor::replace_marker), but fails with
+    /// [`Error::NotFound`] instead of silently doing nothing.
+    ///
+    /// ```
+    /// # use file_editor::{Editor, Error};
+    /// let mut ed = Editor::from_string("version = 1\n");
+    /// let err = ed.try_replace_marker("verison", "version = 2", false).unwrap_err();
+    /// assert!(matches!(err, Error::NotFound { .. }));
+    /// ```
+    pub fn try_replace_marker<'a, M>(
+        &mut self,
+        marker: M,
+        text: &str,
+        same_indent: bool,
+    ) -> Result<&mut Self>
+    where
+        M: Into<Marker<'a>>,
+    {
+        let marker = marker.into();
+        self.require_marker(&marker)?;
+        Ok(self.replace_marker(marker, text, same_indent))
+    }
+
+    /// Like [`erase`](Editor::erase), but first checks the number of matches
+    /// against `expect`; on mismatch the buffer is left untouched.
+    pub fn try_erase<'a, P>(&mut self, pattern: P, expect: Expect) -> Result<&mut Self>
+    where
+        P: Into<Pattern<'a>>,
+    {
+        let pat = pattern.into();
+        self.require_matches(&pat, expect)?;
+        Ok(self.erase(pat))
+    }
+
+    /// Like [`replace`](Editor::replace), but first checks the number of
+    /// matches against `expect`; on mismatch the buffer is left untouched.
+    ///
+    /// ```
+    /// # use file_editor::{Editor, Expect};
+    /// let mut ed = Editor::from_string("a=1\na=1\n");
+    /// assert!(ed.try_replace("a=1", "a=2", Expect::Exactly(1)).is_err());
+    /// ed.try_replace("a=1", "a=2", Expect::AtLeast(2))?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn try_replace<'a, P>(
+        &mut self,
+        pattern: P,
+        replacement: &str,
+        expect: Expect,
+    ) -> Result<&mut Self>
+    where
+        P: Into<Pattern<'a>>,
+    {
+        let pat = pattern.into();
+        self.require_matches(&pat, expect)?;
+        Ok(self.replace(pat, replacement))
+    }
+
+    /// Like [`mask`](Editor::mask), but first checks the number of matches
+    /// against `expect`; on mismatch the buffer is left untouched.
+    pub fn try_mask<'a, P>(&mut self, pattern: P, mask: &str, expect: Expect) -> Result<&mut Self>
+    where
+        P: Into<Pattern<'a>>,
+    {
+        let pat = pattern.into();
+        self.require_matches(&pat, expect)?;
+        Ok(self.mask(pat, mask))
+    }
+
+    fn require_marker(&self, marker: &Marker<'_>) -> Result<()> {
+        if marker.positions(&self.buf).is_empty() {
+            Err(Error::NotFound {
+                marker: marker.text.to_owned(),
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    fn require_matches(&self, pat: &Pattern<'_>, expect: Expect) -> Result<()> {
+        let found = pat.count(&self.buf);
+        if expect.accepts(found) {
+            Ok(())
+        } else {
+            Err(Error::MatchCount {
+                pattern: pat.as_str().to_owned(),
+                expected: expect,
+                found,
+            })
+        }
     }
 }
+
+/// Name and arguments of an editing call, for reports and the journal.
+#[derive(Debug, Clone, Copy)]
+struct Op<'a> {
+    name: &'static str,
+    pattern: Option<&'a str>,
+    text: Option<&'a str>,
+}
+
+impl<'a> Op<'a> {
+    fn new(name: &'static str, pattern: Option<&'a str>, text: Option<&'a str>) -> Self {
+        Self {
+            name,
+            pattern,
+            text,
+        }
+    }
+}
+
+/// `path` as a relative `/`-separated name for diff headers.
+///
+/// The root is dropped and `.` / `..` are resolved lexically, as `git apply`
+/// rejects them; a path climbing above its start is reduced to its file name.
+fn header_path(path: &Path) -> String {
+    let mut parts = Vec::new();
+    for c in path.components() {
+        match c {
+            Component::Prefix(_) | Component::RootDir | Component::CurDir => {}
+            Component::ParentDir => {
+                if parts.pop().is_none() {
+                    return path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                }
+            }
+            Component::Normal(part) => parts.push(part.to_string_lossy()),
+        }
+    }
+    parts.join("/")
+}
+
+/// A file as read from disk.
+struct Loaded {
+    buf: String,
+    stamp: FileStamp,
+    encoding: Encoding,
+    bom: bool,
+}
+
+/// Read and decode `path` (sniffing the encoding unless given) and stamp it.
+fn read(path: &Path, encoding: Option<Encoding>) -> Result<Loaded> {
+    read_inner(path, encoding).at(path)
+}
+
+fn read_inner(path: &Path, encoding: Option<Encoding>) -> Result<Loaded> {
+    let bytes = fs::read(path)?;
+    let stamp = FileStamp::new(&fs::metadata(path)?, &bytes);
+    let (buf, encoding, bom) = Encoding::decode(&bytes, encoding)?;
+    Ok(Loaded {
+        buf,
+        stamp,
+        encoding,
+        bom,
+    })
+}
@@ -0,0 +1,92 @@
+//! Idempotent managed blocks.
+use super::{Editor, Op};
+use crate::anchor::Anchor;
+use crate::block::{self, CommentStyle};
+use crate::edit::Edit;
+use crate::error::Result;
+
+impl Editor {
+    /// Comment syntax of the marker lines written by
+    /// [`ensure_block`](Editor::ensure_block) (`#` by default).
+    pub fn comment_style(&mut self, style: CommentStyle) -> &mut Self {
+        self.comment = style;
+        self
+    }
+
+    /// **Ensure** `content` is present as a managed block named `id`.
+    ///
+    /// The block is wrapped in `# BEGIN <id>` / `# END <id>` lines (see
+    /// [`comment_style`](Editor::comment_style)). If the block exists, its body
+    /// is replaced in place (a no-op when unchanged); otherwise it is inserted
+    /// at `anchor`. Running the same call again never adds a second copy.
+    ///
+    /// Fails with [`Error::Validation`](crate::Error::Validation) if a `BEGIN`
+    /// line has no matching `END` line.
+    ///
+    /// ```
+    /// # use file_editor::{Anchor, Editor};
+    /// let mut ed = Editor::from_string("127.0.0.1 localhost\n");
+    /// for _ in 0..2 {
+    ///     ed.ensure_block("lab", "10.0.0.2 db\n10.0.0.3 cache\n", Anchor::End)?;
+    /// }
+    /// assert_eq!(
+    ///     ed.as_str(),
+    ///     "127.0.0.1 localhost\n# BEGIN lab\n10.0.0.2 db\n10.0.0.3 cache\n# END lab\n"
+    /// );
+    /// # Ok::<(), file_editor::Error>(())
+    /// ```
+    pub fn ensure_block(
+        &mut self,
+        id: &str,
+        content: &str,
+        anchor: Anchor<'_>,
+    ) -> Result<&mut Self> {
+        let op = Op::new("ensure_block", Some(id), Some(content));
+        let (begin, end) = self.block_markers(id);
+        let eol = self.eol.as_str();
+        let mut text = format!("{begin}{eol}{}", self.eol.apply(content));
+        if !text.ends_with('\n') {
+            text.push_str(eol);
+        }
+        text.push_str(&end);
+        text.push_str(eol);
+
+        let edit = match block::find(&self.buf, &begin, &end)? {
+            Some(range) => Edit::new(range, text),
+            None => {
+                let (at, terminate) = anchor.locate(&self.buf);
+                if terminate {
+                    text.insert_str(0, eol);
+                }
+                Edit::insert(at, text)
+            }
+        };
+        Ok(self.splice(op, vec![edit]))
+    }
+
+    /// **Remove** the managed block `id`, marker lines included.
+    ///
+    /// Does nothing if there is no such block.
+    pub fn remove_block(&mut self, id: &str) -> Result<&mut Self> {
+        let op = Op::new("remove_block", Some(id), None);
+        let (begin, end) = self.block_markers(id);
+        let edits = block::find(&self.buf, &begin, &end)?
+            .map(|range| Edit::new(range, ""))
+            .into_iter()
+            .collect();
+        Ok(self.splice(op, edits))
+    }
+
+    /// `true` if a managed block named `id` is present.
+    pub fn has_block(&self, id: &str) -> bool {
+        let (begin, end) = self.block_markers(id);
+        matches!(block::find(&self.buf, &begin, &end), Ok(Some(_)))
+    }
+
+    fn block_markers(&self, id: &str) -> (String, String) {
+        (
+            self.comment.marker("BEGIN", id),
+            self.comment.marker("END", id),
+        )
+    }
+}
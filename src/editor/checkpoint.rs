@@ -0,0 +1,126 @@
+//! Named checkpoints and all-or-nothing transactions.
+use super::Editor;
+use crate::error::{Error, Result};
+use crate::history::History;
+use crate::journal::Journal;
+
+/// Buffer state saved by [`Editor::checkpoint`].
+#[derive(Debug, Clone)]
+pub(super) struct Checkpoint {
+    name: String,
+    buf: String,
+}
+
+impl Editor {
+    /// Remember the current buffer under `name`.
+    ///
+    /// Checkpoints form a stack; a name may be reused, in which case lookups
+    /// find the most recent one.
+    ///
+    /// ```
+    /// # use file_editor::Editor;
+    /// let mut ed = Editor::from_string("[deps]\n");
+    /// ed.checkpoint("before-deps");
+    /// ed.append("serde = \"1\"\n");
+    /// ed.rollback_to("before-deps")?;
+    /// assert_eq!(ed.as_str(), "[deps]\n");
+    /// # Ok::<(), file_editor::Error>(())
+    /// ```
+    pub fn checkpoint(&mut self, name: impl Into<String>) -> &mut Self {
+        self.checkpoints.push(Checkpoint {
+            name: name.into(),
+            buf: self.buf.clone(),
+        });
+        self
+    }
+
+    /// Restore the buffer saved by checkpoint `name`.
+    ///
+    /// The checkpoint itself is kept (roll back to it again at will); any
+    /// checkpoints taken after it are dropped. The rollback is a single
+    /// [`undo`](Editor::undo)able step.
+    pub fn rollback_to(&mut self, name: &str) -> Result<&mut Self> {
+        let i = self.find_checkpoint(name)?;
+        self.checkpoints.truncate(i + 1);
+        let buf = self.checkpoints[i].buf.clone();
+        self.rewrite("rollback_to", buf);
+        Ok(self)
+    }
+
+    /// Forget checkpoint `name` and every checkpoint taken after it,
+    /// keeping the buffer as it is.
+    pub fn release(&mut self, name: &str) -> Result<&mut Self> {
+        let i = self.find_checkpoint(name)?;
+        self.checkpoints.truncate(i);
+        Ok(self)
+    }
+
+    /// Names of the live checkpoints, oldest first.
+    pub fn checkpoints(&self) -> Vec<&str> {
+        self.checkpoints.iter().map(|cp| cp.name.as_str()).collect()
+    }
+
+    /// Run `f` and keep its edits only if it succeeds.
+    ///
+    /// On `Err` the buffer, dirty flag, [`reports`](Editor::reports), undo
+    /// history, [checkpoints](Editor::checkpoints) and
+    /// [journal](Editor::journal) are put back as they were before
+    /// the call, as if it never ran; the error is passed through. Files saved
+    /// inside `f` stay saved.
+    ///
+    /// ```
+    /// # use file_editor::{Editor, Error};
+    /// let mut ed = Editor::from_string("a = 1\n");
+    /// let res = ed.transaction(|e| {
+    ///     e.try_replace("a = 1", "a = 2", Default::default())?;
+    ///     e.try_insert_after("[missing]", "b = 2", false)?;
+    ///     Ok::<_, Error>(())
+    /// });
+    /// assert!(res.is_err());
+    /// assert_eq!(ed.as_str(), "a = 1\n");
+    /// ```
+    pub fn transaction<T, E, F>(&mut self, f: F) -> std::result::Result<T, E>
+    where
+        F: FnOnce(&mut Self) -> std::result::Result<T, E>,
+    {
+        let saved = Saved {
+            buf: self.buf.clone(),
+            dirty: self.dirty,
+            reports: self.reports.len(),
+            history: self.history.clone(),
+            checkpoints: self.checkpoints.clone(),
+            journal: self.journal.as_ref().map(Journal::len),
+        };
+        let result = f(self);
+        if result.is_err() {
+            self.dirty = saved.dirty || saved.buf != self.base;
+            self.buf = saved.buf;
+            self.reports.truncate(saved.reports);
+            self.history = saved.history;
+            self.checkpoints = saved.checkpoints;
+            if let (Some(journal), Some(len)) = (&mut self.journal, saved.journal) {
+                journal.truncate(len);
+            }
+        }
+        result
+    }
+
+    fn find_checkpoint(&self, name: &str) -> Result<usize> {
+        self.checkpoints
+            .iter()
+            .rposition(|cp| cp.name == name)
+            .ok_or_else(|| Error::UnknownCheckpoint {
+                name: name.to_owned(),
+            })
+    }
+}
+
+/// State restored when a [`transaction`](Editor::transaction) fails.
+struct Saved {
+    buf: String,
+    dirty: bool,
+    reports: usize,
+    history: History,
+    checkpoints: Vec<Checkpoint>,
+    journal: Option<usize>,
+}
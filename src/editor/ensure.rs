@@ -0,0 +1,101 @@
+//! Idempotent single-line edits (`lineinfile` semantics).
+use super::lines::{self, Line};
+use super::{Editor, Op};
+use crate::anchor::Anchor;
+use crate::edit::Edit;
+use crate::pattern::Pattern;
+
+impl Editor {
+    /// **Ensure** the buffer contains `line`, appending it if no line equals
+    /// it exactly (line endings aside).
+    ///
+    /// Whether anything was added shows in
+    /// [`last_report`](Editor::last_report)`().changed`.
+    ///
+    /// ```
+    /// # use file_editor::Editor;
+    /// let mut ed = Editor::from_string("PATH=/usr/bin\n");
+    /// ed.ensure_line("umask 022");
+    /// assert!(ed.last_report().unwrap().changed);
+    /// ed.ensure_line("umask 022");
+    /// assert!(!ed.last_report().unwrap().changed);
+    /// assert_eq!(ed.as_str(), "PATH=/usr/bin\numask 022\n");
+    /// ```
+    pub fn ensure_line(&mut self, line: &str) -> &mut Self {
+        let op = Op::new("ensure_line", None, Some(line));
+        let edits = if self.has_line(line) {
+            Vec::new()
+        } else {
+            vec![self.new_line(line, Anchor::End)]
+        };
+        self.splice(op, edits)
+    }
+
+    /// **Ensure** the first line matching `pattern` reads `line`.
+    ///
+    /// That line is replaced (left alone if it already equals `line`); when
+    /// no line matches, `line` is inserted at `anchor` unless it is already
+    /// present. A pattern that does not match its own replacement (`"yes"`
+    /// replaced by `"no"`) therefore still converges.
+    ///
+    /// ```
+    /// # use file_editor::{Anchor, Editor};
+    /// let mut ed = Editor::from_string("[server]\nport = 80\n");
+    /// ed.ensure_line_matching("port =", "port = 8080", Anchor::End)
+    ///     .ensure_line_matching("host =", "host = ::", Anchor::After("[server]".into()));
+    /// assert_eq!(ed.as_str(), "[server]\nhost = ::\nport = 8080\n");
+    /// ```
+    pub fn ensure_line_matching<'a, P>(
+        &mut self,
+        pattern: P,
+        line: &str,
+        anchor: Anchor<'_>,
+    ) -> &mut Self
+    where
+        P: Into<Pattern<'a>>,
+    {
+        let pat = pattern.into();
+        let op = Op::new("ensure_line_matching", Some(pat.as_str()), Some(line));
+        let edits = match self.matching_lines(&pat).next() {
+            Some(found) => vec![Edit::new(found.content, self.eol.apply(line))],
+            None if self.has_line(line) => Vec::new(),
+            None => vec![self.new_line(line, anchor)],
+        };
+        self.splice(op, edits)
+    }
+
+    /// **Remove** every line matching `pattern`, line endings included.
+    pub fn ensure_absent<'a, P>(&mut self, pattern: P) -> &mut Self
+    where
+        P: Into<Pattern<'a>>,
+    {
+        let pat = pattern.into();
+        let op = Op::new("ensure_absent", Some(pat.as_str()), None);
+        let edits = self
+            .matching_lines(&pat)
+            .map(|l| Edit::new(l.full, ""))
+            .collect();
+        self.splice(op, edits)
+    }
+
+    fn has_line(&self, line: &str) -> bool {
+        lines::spans(&self.buf).any(|l| self.buf[l.content] == *line)
+    }
+
+    fn matching_lines<'s>(&'s self, pat: &'s Pattern<'_>) -> impl Iterator<Item = Line> + 's {
+        lines::spans(&self.buf).filter(|l| pat.is_match(&self.buf[l.content.clone()]))
+    }
+
+    /// Insertion of `line` as a whole new line at `anchor`.
+    fn new_line(&self, line: &str, anchor: Anchor<'_>) -> Edit {
+        let (at, terminate) = anchor.locate(&self.buf);
+        let eol = self.eol.as_str();
+        let line = self.eol.apply(line);
+        let text = if terminate {
+            format!("{eol}{line}{eol}")
+        } else {
+            format!("{line}{eol}")
+        };
+        Edit::insert(at, text)
+    }
+}
@@ -0,0 +1,111 @@
+//! Recording and replaying the edit journal.
+use std::{ops::Range, time::SystemTime};
+
+use super::{Editor, Op};
+use crate::edit::Edit;
+use crate::error::{Error, Result};
+use crate::history::Splice;
+use crate::journal::{self, Journal, JournalEntry};
+
+impl Editor {
+    /// Start (or stop) journaling every change made to the buffer.
+    ///
+    /// Turning the journal on starts an empty one; turning it off discards it.
+    ///
+    /// ```
+    /// # use file_editor::Editor;
+    /// let mut ed = Editor::from_string("debug = true\n");
+    /// ed.record_journal(true).replace("true", "false");
+    /// let entry = &ed.journal().unwrap().entries()[0];
+    /// assert_eq!(entry.op, "replace");
+    /// assert_eq!(entry.edits[0].at, 8);
+    /// ```
+    pub fn record_journal(&mut self, on: bool) -> &mut Self {
+        self.journal = on.then(Journal::default);
+        self
+    }
+
+    /// The journal, if [`record_journal`](Editor::record_journal) is on.
+    pub fn journal(&self) -> Option<&Journal> {
+        self.journal.as_ref()
+    }
+
+    /// Take the journal recorded so far, leaving an empty one in its place.
+    pub fn take_journal(&mut self) -> Option<Journal> {
+        self.journal.as_mut().map(std::mem::take)
+    }
+
+    /// Re-apply the changes recorded in `journal` to this buffer.
+    ///
+    /// Every edit must find exactly the text it originally removed, the edits
+    /// of an entry must be in order without overlapping, and the buffer must
+    /// hash to the recorded checksum after each entry; otherwise the buffer
+    /// is left untouched and [`Error::ReplayMismatch`] is returned.
+    ///
+    /// ```
+    /// # use file_editor::Editor;
+    /// let mut live = Editor::from_string("a\n");
+    /// live.record_journal(true).append("b\n").replace("a", "A");
+    ///
+    /// let mut fresh = Editor::from_string("a\n");
+    /// fresh.replay(live.journal().unwrap())?;
+    /// assert_eq!(fresh.as_str(), live.as_str());
+    /// # Ok::<(), file_editor::Error>(())
+    /// ```
+    pub fn replay(&mut self, journal: &Journal) -> Result<&mut Self> {
+        self.transaction(|ed| {
+            for (i, entry) in journal.entries().iter().enumerate() {
+                let mismatch = || Error::ReplayMismatch {
+                    entry: i,
+                    op: entry.op.clone(),
+                };
+                let mut edits: Vec<Edit> = Vec::with_capacity(entry.edits.len());
+                for e in &entry.edits {
+                    let Some(end) = e.at.checked_add(e.removed.len()) else {
+                        return Err(mismatch());
+                    };
+                    // edits must be sorted and disjoint, as recorded
+                    if edits.last().is_some_and(|prev| e.at < prev.range.end) {
+                        return Err(mismatch());
+                    }
+                    let range = e.at..end;
+                    if ed.buf.get(range.clone()) != Some(e.removed.as_str()) {
+                        return Err(mismatch());
+                    }
+                    edits.push(Edit::new(range, e.inserted.clone()));
+                }
+                let op = Op::new("replay", entry.pattern.as_deref(), entry.text.as_deref());
+                ed.splice(op, edits);
+                if journal::checksum(&ed.buf) != entry.checksum {
+                    return Err(mismatch());
+                }
+            }
+            Ok(())
+        })?;
+        Ok(self)
+    }
+
+    /// Append an entry for `op` to the journal (if recording).
+    pub(super) fn log(&mut self, op: Op<'_>, matches: Vec<Range<usize>>, splices: Vec<Splice>) {
+        let Some(journal) = &mut self.journal else {
+            return;
+        };
+        journal.push(JournalEntry {
+            op: op.name.to_owned(),
+            pattern: op.pattern.map(str::to_owned),
+            text: op.text.map(str::to_owned),
+            matches,
+            edits: splices.into_iter().map(Into::into).collect(),
+            timestamp: SystemTime::now(),
+            checksum: journal::checksum(&self.buf),
+        });
+    }
+
+    /// Journal a change from `before` to the current buffer.
+    pub(super) fn log_change(&mut self, name: &'static str, before: Option<String>) {
+        let Some(splice) = before.and_then(|b| Splice::between(&b, &self.buf)) else {
+            return;
+        };
+        self.log(Op::new(name, None, None), Vec::new(), vec![splice]);
+    }
+}
@@ -0,0 +1,155 @@
+//! Line-oriented access and editing, numbered like [`Editor::find_lines`].
+use std::ops::{Bound, Range, RangeBounds};
+
+use super::{Editor, Op};
+use crate::edit::Edit;
+use crate::error::{Error, Result};
+
+/// Byte ranges of one line.
+pub(super) struct Line {
+    /// The text without its line ending.
+    pub content: Range<usize>,
+    /// `content` plus the line ending, if any.
+    pub full: Range<usize>,
+}
+
+/// Every line of `buf`, in order.
+pub(super) fn spans(buf: &str) -> impl Iterator<Item = Line> + '_ {
+    buf.split_inclusive('\n').scan(0, |start, text| {
+        let body = text
+            .strip_suffix('\n')
+            .map_or(text, |t| t.strip_suffix('\r').unwrap_or(t));
+        let line = Line {
+            content: *start..*start + body.len(),
+            full: *start..*start + text.len(),
+        };
+        *start += text.len();
+        Some(line)
+    })
+}
+
+impl Editor {
+    /// Number of lines, as counted by [`lines`](Editor::lines).
+    pub fn line_count(&self) -> usize {
+        self.buf.lines().count()
+    }
+
+    /// Iterate over the lines of the buffer, without line endings.
+    ///
+    /// A final line ending does not start another (empty) line.
+    pub fn lines(&self) -> std::str::Lines<'_> {
+        self.buf.lines()
+    }
+
+    /// Line `n` (1-based) without its line ending, or `None` past the end.
+    pub fn line(&self, n: usize) -> Option<&str> {
+        n.checked_sub(1).and_then(|i| self.buf.lines().nth(i))
+    }
+
+    /// **Replace** the text of line `n`, keeping its line ending.
+    ///
+    /// ```
+    /// # use file_editor::Editor;
+    /// let mut ed = Editor::from_string("a = 1\nb = 2\n");
+    /// let n = ed.find_lines("b =", None)[0];
+    /// ed.replace_line(n, "b = 3")?;
+    /// assert_eq!(ed.as_str(), "a = 1\nb = 3\n");
+    /// # Ok::<(), file_editor::Error>(())
+    /// ```
+    pub fn replace_line(&mut self, n: usize, text: &str) -> Result<&mut Self> {
+        let line = self.line_range(n)?;
+        let op = Op::new("replace_line", None, Some(text));
+        let text = self.eol.apply(text);
+        Ok(self.splice(op, vec![Edit::new(line.content, text)]))
+    }
+
+    /// **Insert** `text` as a new line before line `n`.
+    ///
+    /// `text` should not end with a line ending; one is added.
+    pub fn insert_line_before(&mut self, n: usize, text: &str) -> Result<&mut Self> {
+        let line = self.line_range(n)?;
+        let op = Op::new("insert_line_before", None, Some(text));
+        let text = format!("{}{}", self.eol.apply(text), self.eol.as_str());
+        Ok(self.splice(op, vec![Edit::insert(line.full.start, text)]))
+    }
+
+    /// **Insert** `text` as a new line after line `n`.
+    ///
+    /// `text` should not end with a line ending; one is added (and one is
+    /// added to line `n` first if it is the last line and has none).
+    pub fn insert_line_after(&mut self, n: usize, text: &str) -> Result<&mut Self> {
+        let line = self.line_range(n)?;
+        let op = Op::new("insert_line_after", None, Some(text));
+        let eol = self.eol.as_str();
+        let text = self.eol.apply(text);
+        let text = if line.full.end == line.content.end {
+            format!("{eol}{text}")
+        } else {
+            format!("{text}{eol}")
+        };
+        Ok(self.splice(op, vec![Edit::insert(line.full.end, text)]))
+    }
+
+    /// **Delete** a range of whole lines, line endings included.
+    ///
+    /// Bounds are 1-based line numbers: `40..=55` and `40..56` both delete
+    /// lines 40 through 55; `3..` deletes from line 3 to the end.
+    ///
+    /// ```
+    /// # use file_editor::Editor;
+    /// let mut ed = Editor::from_string("keep\n// TODO\nkeep\n// TODO\n");
+    /// for n in ed.find_lines("TODO", None).into_iter().rev() {
+    ///     ed.delete_lines(n..=n)?;
+    /// }
+    /// assert_eq!(ed.as_str(), "keep\nkeep\n");
+    /// # Ok::<(), file_editor::Error>(())
+    /// ```
+    pub fn delete_lines(&mut self, lines: impl RangeBounds<usize>) -> Result<&mut Self> {
+        let first = match lines.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n.saturating_add(1),
+            Bound::Unbounded => 1,
+        };
+        let last = match lines.end_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n.saturating_sub(1),
+            Bound::Unbounded => self.line_count(),
+        };
+        if first > last {
+            return Err(Error::validation(format!(
+                "line range {first}..={last} is empty"
+            )));
+        }
+        let range = self.line_range(first)?.full.start..self.line_range(last)?.full.end;
+        let op = Op::new("delete_lines", None, None);
+        Ok(self.splice(op, vec![Edit::new(range, "")]))
+    }
+
+    /// **Swap** the text of lines `a` and `b`; line endings stay in place.
+    pub fn swap_lines(&mut self, a: usize, b: usize) -> Result<&mut Self> {
+        let (a, b) = (a.min(b), a.max(b));
+        let (first, second) = (self.line_range(a)?.content, self.line_range(b)?.content);
+        let op = Op::new("swap_lines", None, None);
+        let edits = if a == b {
+            Vec::new()
+        } else {
+            vec![
+                Edit::new(first.clone(), &self.buf[second.clone()]),
+                Edit::new(second, &self.buf[first]),
+            ]
+        };
+        Ok(self.splice(op, edits))
+    }
+
+    /// Byte ranges of line `n`, or a validation error if there is no such line.
+    fn line_range(&self, n: usize) -> Result<Line> {
+        n.checked_sub(1)
+            .and_then(|i| spans(&self.buf).nth(i))
+            .ok_or_else(|| {
+                Error::validation(format!(
+                    "line {n} is out of range (the buffer has {} lines)",
+                    self.line_count()
+                ))
+            })
+    }
+}
@@ -0,0 +1,66 @@
+//! Merging external changes into the buffer on save.
+use std::path::Path;
+
+use super::{Editor, read};
+use crate::error::{Error, Result};
+use crate::merge::{self, MergeStrategy};
+
+impl Editor {
+    /// Choose what [`save`](Editor::save) does when the file changed on disk
+    /// since it was opened.
+    ///
+    /// With [`Markers`](MergeStrategy::Markers) or
+    /// [`Report`](MergeStrategy::Report), `save` runs a three-way merge between
+    /// the contents at open (base), the buffer (ours) and the file on disk
+    /// (theirs). Changes to different lines merge automatically and the result
+    /// is saved; [`SaveReport::merged`](crate::SaveReport::merged) is set.
+    /// Overlapping changes fail with [`Error::MergeConflict`] and write nothing:
+    /// with `Markers` the buffer then holds git-style conflict markers, and the
+    /// next `save` writes whatever it contains by then.
+    ///
+    /// A file deleted on disk is always an [`Error::Conflict`].
+    ///
+    /// ```no_run
+    /// # use file_editor::{Editor, Error, MergeStrategy};
+    /// # fn run() -> file_editor::Result<()> {
+    /// let mut ed = Editor::open("hosts")?;
+    /// ed.merge_strategy(MergeStrategy::Report).append("10.0.0.2 db\n");
+    /// match ed.save() {
+    ///     Err(Error::MergeConflict { conflicts, .. }) => {
+    ///         for c in conflicts {
+    ///             eprintln!("line {}: {:?} vs {:?}", c.line, c.ours, c.theirs);
+    ///         }
+    ///     }
+    ///     other => { other?; }
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn merge_strategy(&mut self, strategy: MergeStrategy) -> &mut Self {
+        self.merge = strategy;
+        self
+    }
+
+    /// Merge the file at `path` into the buffer and adopt it as the new base.
+    pub(super) fn merge_from_disk(&mut self, path: &Path) -> Result<()> {
+        let theirs = read(path, Some(self.encoding))?;
+        let (text, conflicts) =
+            merge::merge3(&self.base, &self.buf, &theirs.buf, self.eol.as_str());
+        if !conflicts.is_empty() && self.merge == MergeStrategy::Report {
+            return Err(Error::MergeConflict {
+                path: path.to_owned(),
+                conflicts,
+            });
+        }
+        self.rewrite("merge", text);
+        self.base = theirs.buf;
+        self.stamp = Some(theirs.stamp);
+        if conflicts.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::MergeConflict {
+                path: path.to_owned(),
+                conflicts,
+            })
+        }
+    }
+}
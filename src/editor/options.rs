@@ -0,0 +1,210 @@
+//! `OpenOptions`-style builder for [`Editor`].
+use std::{
+    fs::{self, OpenOptions},
+    io,
+    path::Path,
+    time::Duration,
+};
+
+use super::Editor;
+use crate::atomic;
+use crate::encoding::Encoding;
+use crate::error::{Context, Error, Result};
+use crate::lock::{self, Held, Lock};
+use crate::metadata::Preserve;
+
+/// Options and flags controlling how an [`Editor`] is opened, modelled on
+/// [`std::fs::OpenOptions`].
+///
+/// ```no_run
+/// # use file_editor::EditorOptions;
+/// # fn run() -> std::io::Result<()> {
+/// EditorOptions::new()
+///     .create(true)        // open, or start empty if missing
+///     .lazy(true)          // …but only create the file on first save
+///     .create_dirs(true)   // make missing parent directories
+///     .open("out/report.md")?
+///     .append("# Report\n")
+///     .save()?;
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EditorOptions {
+    create: bool,
+    create_new: bool,
+    truncate: bool,
+    lazy: bool,
+    create_dirs: bool,
+    mode: Option<u32>,
+    encoding: Option<Encoding>,
+    preserve: Option<Preserve>,
+    lock: Option<Lock>,
+    lock_timeout: Duration,
+}
+
+impl EditorOptions {
+    /// All flags off: [`open`](EditorOptions::open) behaves like [`Editor::open`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create the file if it does not exist (**open-or-create**).
+    pub fn create(&mut self, create: bool) -> &mut Self {
+        self.create = create;
+        self
+    }
+
+    /// Create the file, **failing** with `AlreadyExists` if it is already there.
+    ///
+    /// Takes precedence over [`create`](EditorOptions::create) and
+    /// [`truncate`](EditorOptions::truncate).
+    pub fn create_new(&mut self, create_new: bool) -> &mut Self {
+        self.create_new = create_new;
+        self
+    }
+
+    /// Discard the existing contents of the file.
+    pub fn truncate(&mut self, truncate: bool) -> &mut Self {
+        self.truncate = truncate;
+        self
+    }
+
+    /// Touch nothing on open: a missing file is created (and an existing one
+    /// truncated) by the first [`save`](Editor::save).
+    ///
+    /// A lazily created editor starts dirty, so `save` always writes it.
+    pub fn lazy(&mut self, lazy: bool) -> &mut Self {
+        self.lazy = lazy;
+        self
+    }
+
+    /// Create missing parent directories when the file is created.
+    pub fn create_dirs(&mut self, create_dirs: bool) -> &mut Self {
+        self.create_dirs = create_dirs;
+        self
+    }
+
+    /// Permission bits for a newly created file, applied exactly (the umask
+    /// is not consulted).
+    #[cfg(unix)]
+    pub fn mode(&mut self, mode: u32) -> &mut Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// Read (and save) the file in `encoding` instead of sniffing it.
+    pub fn encoding(&mut self, encoding: Encoding) -> &mut Self {
+        self.encoding = Some(encoding);
+        self
+    }
+
+    /// Metadata kept across saves; see [`Editor::preserve`].
+    pub fn preserve(&mut self, preserve: Preserve) -> &mut Self {
+        self.preserve = Some(preserve);
+        self
+    }
+
+    /// Lock the file before reading it and keep the lock until the editor is
+    /// dropped or [`unlock`](Editor::unlock)ed.
+    ///
+    /// If the lock is busy, `open` fails with [`Error::Locked`] once the
+    /// [`lock_timeout`](EditorOptions::lock_timeout) has passed.
+    ///
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # use file_editor::{EditorOptions, Lock};
+    /// # fn run() -> std::io::Result<()> {
+    /// EditorOptions::new()
+    ///     .lock(Lock::Advisory)
+    ///     .lock_timeout(Duration::from_secs(5))
+    ///     .open("jobs.conf")?
+    ///     .append("job = nightly\n")
+    ///     .save()?;
+    /// # Ok(()) }
+    /// ```
+    pub fn lock(&mut self, lock: Lock) -> &mut Self {
+        self.lock = Some(lock);
+        self
+    }
+
+    /// How long to wait for a busy lock (default: fail immediately).
+    pub fn lock_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.lock_timeout = timeout;
+        self
+    }
+
+    /// Open `path` with these options.
+    pub fn open<P: AsRef<Path>>(&self, path: P) -> Result<Editor> {
+        let path = path.as_ref();
+        self.open_inner(path).at(path)
+    }
+
+    fn open_inner(&self, path: &Path) -> Result<Editor> {
+        let exists = if (self.create || self.create_new) && !self.lazy {
+            if self.create_dirs {
+                create_parent(path)?;
+            }
+            // Decided by the create itself: another process may create the
+            // file between a separate check and the open.
+            match OpenOptions::new().write(true).create_new(true).open(path) {
+                Ok(file) => {
+                    if let Some(mode) = self.mode {
+                        atomic::set_mode(&file, mode)?;
+                    }
+                    false
+                }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists && !self.create_new => true,
+                Err(e) => return Err(e.into()),
+            }
+        } else {
+            path.try_exists()?
+        };
+
+        if exists && self.create_new {
+            return Err(io::Error::from(io::ErrorKind::AlreadyExists).into());
+        }
+
+        let guard = match self.lock {
+            Some(Lock::Advisory) if !exists && self.lazy => {
+                return Err(Error::validation(
+                    "advisory locks need an existing file; use `Lock::Sidecar`",
+                ));
+            }
+            Some(kind) => Some(lock::acquire(path, kind, self.lock_timeout)?),
+            None => None,
+        };
+
+        let mut ed = if !exists && self.lazy && (self.create || self.create_new) {
+            let mut ed = Editor::from_string("");
+            ed.path = Some(path.to_owned());
+            ed.encoding = self.encoding.unwrap_or_default();
+            ed.dirty = true;
+            ed
+        } else {
+            if exists && self.truncate && !self.lazy {
+                fs::write(path, "")?;
+            }
+            // a missing file without `create` fails here with `NotFound`
+            let mut ed = Editor::load(path, self.encoding)?;
+            if exists && self.truncate && self.lazy {
+                ed.buf.clear();
+                ed.dirty = true;
+            }
+            ed
+        };
+
+        ed.create_dirs = self.create_dirs;
+        ed.mode = self.mode;
+        ed.preserve = self.preserve.unwrap_or_default();
+        ed.lock = Held(guard);
+        Ok(ed)
+    }
+}
+
+/// `mkdir -p` for the directory holding `path`.
+pub(super) fn create_parent(path: &Path) -> io::Result<()> {
+    match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => fs::create_dir_all(dir),
+        _ => Ok(()),
+    }
+}
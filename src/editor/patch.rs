@@ -0,0 +1,113 @@
+//! Applying unified diffs to the buffer.
+use super::{Editor, Op};
+use crate::atomic;
+use crate::edit::Edit;
+use crate::error::{Context, Error, Result};
+use crate::patch::{self, HunkStatus, PatchOptions, PatchReport};
+
+impl Editor {
+    /// Apply a unified diff to the buffer with default [`PatchOptions`]
+    /// (fuzz 2, any offset, no `.rej` file).
+    ///
+    /// Hunks that do not match are skipped and reported as
+    /// [`Rejected`](HunkStatus::Rejected); the others are applied as one
+    /// editing step. Nothing is written until [`save`](Editor::save), so
+    /// backups, atomic writes and conflict checks work as for any other edit.
+    ///
+    /// ```
+    /// # use file_editor::Editor;
+    /// let mut ed = Editor::from_string("a\nb\nc\n");
+    /// let report = ed.apply_patch("--- a/f\n+++ b/f\n@@ -2 +2 @@\n-b\n+B\n")?;
+    /// assert!(report.is_clean());
+    /// assert_eq!(ed.as_str(), "a\nB\nc\n");
+    /// # Ok::<(), file_editor::Error>(())
+    /// ```
+    pub fn apply_patch(&mut self, patch: &str) -> Result<PatchReport> {
+        self.apply_patch_with(patch, &PatchOptions::default())
+    }
+
+    /// [`apply_patch`](Editor::apply_patch) with explicit tolerances.
+    ///
+    /// A patch touching several files must contain a section for this
+    /// editor's path (compared without the `a/` / `b/` prefixes).
+    pub fn apply_patch_with(&mut self, patch: &str, opts: &PatchOptions) -> Result<PatchReport> {
+        let mut files = patch::parse(patch)?;
+        let file = if files.len() == 1 {
+            files.remove(0)
+        } else {
+            let path = self.path.as_deref().ok_or_else(|| {
+                Error::validation(
+                    "patch touches several files; an in-memory editor cannot pick one",
+                )
+            })?;
+            let i = files.iter().position(|f| f.is_for(path)).ok_or_else(|| {
+                Error::validation(format!("patch has no section for {}", path.display()))
+            })?;
+            files.swap_remove(i)
+        };
+        if opts.reject_file && self.path.is_none() {
+            return Err(Error::validation(
+                "in-memory editor has no path for a `.rej` file",
+            ));
+        }
+
+        let lines: Vec<&str> = self.buf.split_inclusive('\n').collect();
+        let mut starts = Vec::with_capacity(lines.len() + 1);
+        let mut pos = 0;
+        for line in &lines {
+            starts.push(pos);
+            pos += line.len();
+        }
+        starts.push(pos);
+
+        let mut statuses = Vec::with_capacity(file.hunks.len());
+        let mut edits = Vec::new();
+        let mut rejects = String::new();
+        let (mut min, mut carry) = (0, 0);
+        for hunk in &file.hunks {
+            match hunk.locate(&lines, min, carry, opts) {
+                Some(found) => {
+                    edits.push(Edit::new(
+                        starts[found.at]..starts[found.at + found.len],
+                        found.new,
+                    ));
+                    min = found.at + found.len;
+                    carry = found.offset;
+                    statuses.push(if found.offset == 0 && found.fuzz == 0 {
+                        HunkStatus::Applied
+                    } else {
+                        HunkStatus::AppliedWithOffset {
+                            offset: found.offset,
+                            fuzz: found.fuzz,
+                        }
+                    });
+                }
+                None => {
+                    rejects.push_str(&hunk.raw);
+                    statuses.push(HunkStatus::Rejected);
+                }
+            }
+        }
+
+        let mut reject_file = None;
+        if opts.reject_file && !rejects.is_empty() && !self.dry_run {
+            let path = self.require_path()?;
+            let mut name = path.as_os_str().to_owned();
+            name.push(".rej");
+            let rej = std::path::PathBuf::from(name);
+            let text = format!("--- {}\n+++ {}\n{rejects}", file.old_path, file.new_path);
+            let opts = atomic::Options {
+                fsync: self.fsync,
+                ..atomic::Options::default()
+            };
+            atomic::write(&rej, text.as_bytes(), &opts).at(&rej)?;
+            reject_file = Some(rej);
+        }
+
+        self.splice(Op::new("apply_patch", None, Some(patch)), edits);
+        Ok(PatchReport {
+            hunks: statuses,
+            reject_file,
+        })
+    }
+}
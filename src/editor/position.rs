@@ -0,0 +1,73 @@
+//! Editing by line/column instead of by marker.
+use super::{Editor, Op};
+use crate::edit::Edit;
+use crate::error::{Error, Result};
+use crate::position::{self, Position, Span};
+
+impl Editor {
+    /// Byte offset of a line/column [`Position`] in the buffer.
+    ///
+    /// Fails with [`Error::Validation`] if the line does not exist or the
+    /// column is past the end of the line.
+    pub fn offset_of(&self, pos: impl Into<Position>) -> Result<usize> {
+        position::offset(&self.buf, pos.into())
+    }
+
+    /// Line/column [`Position`] of a byte offset in the buffer.
+    ///
+    /// Fails with [`Error::Validation`] if `offset` is past the end or inside
+    /// a multi-byte character.
+    pub fn position_of(&self, offset: usize) -> Result<Position> {
+        position::position(&self.buf, offset)
+    }
+
+    /// **Insert** `text` at a line/column position.
+    ///
+    /// ```
+    /// # use file_editor::Editor;
+    /// let mut ed = Editor::from_string("let x = 1\n");
+    /// ed.insert_at((1, 10), ";")?; // as reported by a compiler
+    /// assert_eq!(ed.as_str(), "let x = 1;\n");
+    /// # Ok::<(), file_editor::Error>(())
+    /// ```
+    pub fn insert_at(&mut self, pos: impl Into<Position>, text: &str) -> Result<&mut Self> {
+        let at = self.offset_of(pos)?;
+        let op = Op::new("insert_at", None, Some(text));
+        let text = self.eol.apply(text);
+        Ok(self.splice(op, vec![Edit::insert(at, text)]))
+    }
+
+    /// **Replace** the text covered by `span` with `text`.
+    ///
+    /// ```
+    /// # use file_editor::{Editor, Span};
+    /// let mut ed = Editor::from_string("fn main() {\n    let müll = 1;\n}\n");
+    /// ed.replace_range(Span::new((2, 9), (2, 13)), "trash")?;
+    /// assert_eq!(ed.as_str(), "fn main() {\n    let trash = 1;\n}\n");
+    /// # Ok::<(), file_editor::Error>(())
+    /// ```
+    pub fn replace_range(&mut self, span: Span, text: &str) -> Result<&mut Self> {
+        let range = self.byte_range(span)?;
+        let op = Op::new("replace_range", None, Some(text));
+        let text = self.eol.apply(text);
+        Ok(self.splice(op, vec![Edit::new(range, text)]))
+    }
+
+    /// **Delete** the text covered by `span`; a span ending at column 1 of
+    /// the next line removes the line ending too.
+    pub fn delete_range(&mut self, span: Span) -> Result<&mut Self> {
+        let range = self.byte_range(span)?;
+        let op = Op::new("delete_range", None, None);
+        Ok(self.splice(op, vec![Edit::new(range, "")]))
+    }
+
+    fn byte_range(&self, span: Span) -> Result<std::ops::Range<usize>> {
+        let (start, end) = (self.offset_of(span.start)?, self.offset_of(span.end)?);
+        if start > end {
+            return Err(Error::validation(format!(
+                "span {span} ends before it starts"
+            )));
+        }
+        Ok(start..end)
+    }
+}
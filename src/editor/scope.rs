@@ -0,0 +1,242 @@
+//! Editing restricted to the regions between two markers.
+use std::ops::Range;
+
+use super::{Editor, Op};
+use crate::edit::Edit;
+use crate::pattern::Pattern;
+
+/// A view of an [`Editor`] restricted to the regions between a start and an
+/// end pattern, returned by [`Editor::within`].
+///
+/// Regions are found afresh before every operation, so earlier edits never
+/// leave a scope pointing at stale offsets. Every start marker opens a
+/// region (repeated regions are all edited); a start marker inside a region
+/// nests, and the region closes at the matching end marker. A region whose
+/// end marker is missing extends to the end of the buffer (or of the
+/// enclosing scope). The end marker may overlap the next start marker, as
+/// `"\n["` does with the following TOML section header.
+///
+/// ```
+/// # use file_editor::Editor;
+/// let mut ed = Editor::from_string(
+///     "[profile.dev]\ndebug = true\n\n[profile.release]\ndebug = true\n",
+/// );
+/// ed.within("[profile.release]", "\n[").replace("debug = true", "debug = false");
+/// assert_eq!(
+///     ed.as_str(),
+///     "[profile.dev]\ndebug = true\n\n[profile.release]\ndebug = false\n",
+/// );
+/// ```
+#[derive(Debug)]
+pub struct Scope<'e, 'p> {
+    editor: &'e mut Editor,
+    /// Start / end markers and inclusiveness, outermost first.
+    levels: Vec<Level<'p>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Level<'p> {
+    start: Pattern<'p>,
+    end: Pattern<'p>,
+    inclusive: bool,
+}
+
+impl Editor {
+    /// Restrict editing to the text between `start` and `end` (exclusive of
+    /// the markers themselves), see [`Scope`].
+    pub fn within<'p, S, E>(&mut self, start: S, end: E) -> Scope<'_, 'p>
+    where
+        S: Into<Pattern<'p>>,
+        E: Into<Pattern<'p>>,
+    {
+        Scope {
+            editor: self,
+            levels: vec![Level {
+                start: start.into(),
+                end: end.into(),
+                inclusive: false,
+            }],
+        }
+    }
+}
+
+impl<'p> Scope<'_, 'p> {
+    /// Include the start and end markers in the regions (off by default).
+    pub fn inclusive(&mut self, on: bool) -> &mut Self {
+        if let Some(level) = self.levels.last_mut() {
+            level.inclusive = on;
+        }
+        self
+    }
+
+    /// Narrow this scope further to the text between `start` and `end`
+    /// inside each of its regions.
+    ///
+    /// ```
+    /// # use file_editor::Editor;
+    /// let mut ed = Editor::from_string("<a>x<b>x</b>x</a>x");
+    /// ed.within("<a>", "</a>").within("<b>", "</b>").replace("x", "y");
+    /// assert_eq!(ed.as_str(), "<a>x<b>y</b>x</a>x");
+    /// ```
+    pub fn within<S, E>(&mut self, start: S, end: E) -> Scope<'_, 'p>
+    where
+        S: Into<Pattern<'p>>,
+        E: Into<Pattern<'p>>,
+    {
+        let mut levels = self.levels.clone();
+        levels.push(Level {
+            start: start.into(),
+            end: end.into(),
+            inclusive: false,
+        });
+        Scope {
+            editor: self.editor,
+            levels,
+        }
+    }
+
+    /// Byte ranges of the regions in the current buffer.
+    pub fn regions(&self) -> Vec<Range<usize>> {
+        let buf = &self.editor.buf;
+        let whole = std::iter::once(0..buf.len()).collect();
+        self.levels.iter().fold(whole, |outer: Vec<_>, level| {
+            outer
+                .into_iter()
+                .flat_map(|r| level.regions(buf, r))
+                .collect()
+        })
+    }
+
+    /// Like [`Editor::find_lines`], counting only matches inside the regions.
+    pub fn find_lines<'a, P>(&self, pattern: P, limit: Option<usize>) -> Vec<usize>
+    where
+        P: Into<Pattern<'a>>,
+    {
+        let pat = pattern.into();
+        let buf = &self.editor.buf;
+        let mut lines: Vec<usize> = self
+            .edits(&pat, "")
+            .iter()
+            .map(|e| buf[..e.range.start].matches('\n').count() + 1)
+            .collect();
+        lines.dedup();
+        lines.truncate(limit.unwrap_or(usize::MAX));
+        lines
+    }
+
+    /// [`Editor::erase`] inside the regions.
+    pub fn erase<'a, P>(&mut self, pattern: P) -> &mut Self
+    where
+        P: Into<Pattern<'a>>,
+    {
+        let pat = pattern.into();
+        let edits = self.edits(&pat, "");
+        self.editor
+            .splice(Op::new("erase", Some(pat.as_str()), None), edits);
+        self
+    }
+
+    /// [`Editor::replace`] inside the regions.
+    pub fn replace<'a, P>(&mut self, pattern: P, replacement: &str) -> &mut Self
+    where
+        P: Into<Pattern<'a>>,
+    {
+        let pat = pattern.into();
+        let op = Op::new("replace", Some(pat.as_str()), Some(replacement));
+        let replacement = self.editor.eol.apply(replacement);
+        let edits = self.edits(&pat, &replacement);
+        self.editor.splice(op, edits);
+        self
+    }
+
+    /// [`Editor::mask`] inside the regions.
+    ///
+    /// ```
+    /// # use file_editor::Editor;
+    /// let mut ed = Editor::from_string("token=a\n# BEGIN secrets\ntoken=b\n# END secrets\n");
+    /// ed.within("# BEGIN secrets", "# END secrets").mask("token=b", "token=***");
+    /// assert_eq!(ed.as_str(), "token=a\n# BEGIN secrets\ntoken=***\n# END secrets\n");
+    /// ```
+    pub fn mask<'a, P>(&mut self, pattern: P, mask: &str) -> &mut Self
+    where
+        P: Into<Pattern<'a>>,
+    {
+        let pat = pattern.into();
+        let op = Op::new("mask", Some(pat.as_str()), Some(mask));
+        let mask = self.editor.eol.apply(mask);
+        let edits = self.edits(&pat, &mask);
+        self.editor.splice(op, edits);
+        self
+    }
+
+    /// Edits for every match of `pat` inside the regions, as buffer offsets.
+    fn edits(&self, pat: &Pattern<'_>, repl: &str) -> Vec<Edit> {
+        let buf = &self.editor.buf;
+        self.regions()
+            .into_iter()
+            .flat_map(|r| {
+                pat.edits(&buf[r.clone()], repl)
+                    .into_iter()
+                    .map(move |mut e| {
+                        e.range = e.range.start + r.start..e.range.end + r.start;
+                        e
+                    })
+            })
+            .collect()
+    }
+}
+
+impl Level<'_> {
+    /// Regions of `buf` inside `within`, with nested start/end pairs balanced.
+    fn regions(&self, buf: &str, within: Range<usize>) -> Vec<Range<usize>> {
+        let hay = &buf[..within.end];
+        let mut out = Vec::new();
+        let mut pos = within.start;
+        while let Some(open) = self.start.find_at(hay, pos) {
+            let mut depth = 1;
+            let mut at = open.end;
+            let close = loop {
+                let Some(end) = self.end.find_at(hay, at) else {
+                    break None;
+                };
+                match self.start.find_at(hay, at) {
+                    Some(nested) if nested.start < end.start && nested.end > at => {
+                        depth += 1;
+                        at = nested.end;
+                    }
+                    _ if depth == 1 => break Some(end),
+                    _ => {
+                        depth -= 1;
+                        at = end.end;
+                    }
+                }
+            };
+            let from = if self.inclusive { open.start } else { open.end };
+            let Some(close) = close else {
+                push_merged(&mut out, from..within.end);
+                break;
+            };
+            let to = if self.inclusive {
+                close.end
+            } else {
+                close.start
+            };
+            push_merged(&mut out, from..to);
+            // the end marker may also begin the next region (`\n[` before a section)
+            let Some(c) = hay[close.start..].chars().next() else {
+                break;
+            };
+            pos = close.start + c.len_utf8();
+        }
+        out
+    }
+}
+
+/// Append `region`, merging it into the previous one when inclusive bounds
+/// make them overlap, so regions stay sorted and disjoint.
+fn push_merged(out: &mut Vec<Range<usize>>, region: Range<usize>) {
+    match out.last_mut() {
+        Some(last) if region.start < last.end => last.end = last.end.max(region.end),
+        _ => out.push(region),
+    }
+}
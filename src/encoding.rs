@@ -0,0 +1,173 @@
+//! Text encodings: BOM sniffing, UTF-8/UTF-16 and (opt-in) legacy code pages.
+use crate::error::{Error, Result};
+
+/// Character encoding of the file on disk.
+///
+/// The buffer itself is always a Rust `String`; the encoding only matters
+/// when reading and saving. More variants exist with the `legacy-encodings`
+/// feature, so matches on it need a wildcard arm.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Encoding {
+    /// UTF-8 (default).
+    #[default]
+    Utf8,
+    /// UTF-16, little endian.
+    Utf16Le,
+    /// UTF-16, big endian.
+    Utf16Be,
+    /// ISO-8859-1 (opt-in via `features = ["legacy-encodings"]`).
+    #[cfg(feature = "legacy-encodings")]
+    Latin1,
+    /// Windows-1252 (opt-in via `features = ["legacy-encodings"]`).
+    #[cfg(feature = "legacy-encodings")]
+    Windows1252,
+}
+
+/// Windows-1252 code points for bytes `0x80..=0x9F`; the five undefined
+/// bytes map to the matching C1 control, as in the WHATWG encoding spec.
+#[cfg(feature = "legacy-encodings")]
+const CP1252_HIGH: [char; 32] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+];
+
+impl Encoding {
+    /// Byte-order mark of this encoding (empty for legacy code pages).
+    pub fn bom(self) -> &'static [u8] {
+        match self {
+            Encoding::Utf8 => b"\xEF\xBB\xBF",
+            Encoding::Utf16Le => b"\xFF\xFE",
+            Encoding::Utf16Be => b"\xFE\xFF",
+            #[cfg(feature = "legacy-encodings")]
+            Encoding::Latin1 | Encoding::Windows1252 => b"",
+        }
+    }
+
+    /// Decode `bytes`, returning the text, the encoding and whether a BOM was present.
+    ///
+    /// With `explicit = None` the encoding is sniffed from the BOM (UTF-8 when
+    /// there is none); otherwise the given encoding is used and its BOM, if
+    /// any, is stripped.
+    pub(crate) fn decode(
+        bytes: &[u8],
+        explicit: Option<Encoding>,
+    ) -> Result<(String, Encoding, bool)> {
+        let enc = explicit.unwrap_or_else(|| {
+            [Encoding::Utf8, Encoding::Utf16Le, Encoding::Utf16Be]
+                .into_iter()
+                .find(|e| bytes.starts_with(e.bom()))
+                .unwrap_or(Encoding::Utf8)
+        });
+        let bom = !enc.bom().is_empty() && bytes.starts_with(enc.bom());
+        let body = &bytes[if bom { enc.bom().len() } else { 0 }..];
+
+        let text = match enc {
+            Encoding::Utf8 => {
+                String::from_utf8(body.to_vec()).map_err(|e| Error::encoding(enc, e))?
+            }
+            Encoding::Utf16Le | Encoding::Utf16Be => {
+                let pairs = body.chunks_exact(2);
+                if !pairs.remainder().is_empty() {
+                    return Err(Error::encoding(enc, "odd number of bytes in UTF-16 input"));
+                }
+                let units: Vec<u16> = pairs
+                    .map(|c| match enc {
+                        Encoding::Utf16Le => u16::from_le_bytes([c[0], c[1]]),
+                        _ => u16::from_be_bytes([c[0], c[1]]),
+                    })
+                    .collect();
+                String::from_utf16(&units).map_err(|e| Error::encoding(enc, e))?
+            }
+            #[cfg(feature = "legacy-encodings")]
+            Encoding::Latin1 => body.iter().map(|&b| char::from(b)).collect(),
+            #[cfg(feature = "legacy-encodings")]
+            Encoding::Windows1252 => body
+                .iter()
+                .map(|&b| match b {
+                    0x80..=0x9F => CP1252_HIGH[usize::from(b - 0x80)],
+                    _ => char::from(b),
+                })
+                .collect(),
+        };
+        Ok((text, enc, bom))
+    }
+
+    /// Encode `text`, prefixed with the BOM when `bom` is `true`.
+    pub(crate) fn encode(self, text: &str, bom: bool) -> Result<Vec<u8>> {
+        let mut out = Vec::with_capacity(text.len() + 3);
+        if bom {
+            out.extend_from_slice(self.bom());
+        }
+        match self {
+            Encoding::Utf8 => out.extend_from_slice(text.as_bytes()),
+            Encoding::Utf16Le => text
+                .encode_utf16()
+                .for_each(|u| out.extend_from_slice(&u.to_le_bytes())),
+            Encoding::Utf16Be => text
+                .encode_utf16()
+                .for_each(|u| out.extend_from_slice(&u.to_be_bytes())),
+            #[cfg(feature = "legacy-encodings")]
+            Encoding::Latin1 | Encoding::Windows1252 => {
+                for c in text.chars() {
+                    out.push(self.encode_byte(c).ok_or_else(|| {
+                        Error::encoding(self, format!("character {c:?} cannot be encoded"))
+                    })?);
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    #[cfg(feature = "legacy-encodings")]
+    fn encode_byte(self, c: char) -> Option<u8> {
+        if self == Encoding::Windows1252 {
+            if let Some(i) = CP1252_HIGH.iter().position(|&h| h == c) {
+                return Some(0x80 + i as u8);
+            }
+            if ('\u{80}'..='\u{9F}').contains(&c) {
+                return None;
+            }
+        }
+        u8::try_from(u32::from(c)).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Encoding;
+
+    #[test]
+    fn sniff_boms() {
+        let (t, e, bom) = Encoding::decode(b"\xEF\xBB\xBFhi", None).unwrap();
+        assert_eq!((t.as_str(), e, bom), ("hi", Encoding::Utf8, true));
+        let (t, e, bom) = Encoding::decode(b"\xFE\xFF\x00h\x00i", None).unwrap();
+        assert_eq!((t.as_str(), e, bom), ("hi", Encoding::Utf16Be, true));
+        let (t, e, bom) = Encoding::decode(b"hi", None).unwrap();
+        assert_eq!((t.as_str(), e, bom), ("hi", Encoding::Utf8, false));
+    }
+
+    #[test]
+    fn utf16_round_trip() {
+        let bytes = Encoding::Utf16Le.encode("h\u{e9}\u{1F980}", true).unwrap();
+        let (t, e, bom) = Encoding::decode(&bytes, None).unwrap();
+        assert_eq!(
+            (t.as_str(), e, bom),
+            ("h\u{e9}\u{1F980}", Encoding::Utf16Le, true)
+        );
+        assert!(Encoding::decode(b"\xFF\xFEx", None).is_err());
+    }
+
+    #[cfg(feature = "legacy-encodings")]
+    #[test]
+    fn windows_1252() {
+        let (t, ..) = Encoding::decode(b"\x80 caf\xE9", Some(Encoding::Windows1252)).unwrap();
+        assert_eq!(t, "\u{20AC} caf\u{e9}");
+        let back = Encoding::Windows1252.encode(&t, false).unwrap();
+        assert_eq!(back, b"\x80 caf\xE9");
+        assert!(Encoding::Latin1.encode("\u{20AC}", false).is_err());
+        assert!(Encoding::Windows1252.encode("\u{80}", false).is_err());
+    }
+}
@@ -0,0 +1,284 @@
+//! The crate-wide error type.
+use std::{
+    error, fmt, io,
+    path::{Path, PathBuf},
+};
+
+use crate::encoding::Encoding;
+use crate::expect::Expect;
+use crate::merge::MergeConflict;
+
+/// `Result` with [`Error`] as the default error type.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Everything that can go wrong while editing a file.
+///
+/// Converts losslessly to and from [`io::Error`]: functions returning
+/// `io::Result` can use `?` on editor calls, and [`Error::from`] recovers the
+/// original variant from an `io::Error` that started out as an `Error`.
+///
+/// ```no_run
+/// # use file_editor::{Editor, Error};
+/// match Editor::open("app.conf").and_then(|mut ed| {
+///     ed.try_replace_marker("# END", "x = 1\n# END", false)?;
+///     ed.save().map(|_| ())
+/// }) {
+///     Err(Error::NotFound { marker }) => eprintln!("no {marker} in app.conf"),
+///     Err(Error::Conflict { .. }) => eprintln!("app.conf changed, try again"),
+///     other => other.unwrap(),
+/// }
+/// ```
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// An I/O operation failed.
+    Io {
+        /// File involved, when known.
+        path: Option<PathBuf>,
+        /// The underlying error.
+        source: io::Error,
+    },
+    /// Bytes are not valid in the file's encoding, or a character cannot be
+    /// represented in it.
+    Encoding {
+        /// File involved, when known.
+        path: Option<PathBuf>,
+        /// Encoding being decoded from or encoded to.
+        encoding: Encoding,
+        /// What exactly was wrong.
+        source: Box<dyn error::Error + Send + Sync>,
+    },
+    /// A marker (or the selected [`Occurrence`](crate::Occurrence) of it) does
+    /// not occur in the buffer (`try_*` methods).
+    NotFound {
+        /// The marker that was searched for.
+        marker: String,
+    },
+    /// A pattern matched an unexpected number of times (`try_*` methods).
+    MatchCount {
+        /// Source of the pattern (literal text or regex).
+        pattern: String,
+        /// What was asked for.
+        expected: Expect,
+        /// What was found.
+        found: usize,
+    },
+    /// A regular expression failed to compile.
+    #[cfg(feature = "regex")]
+    Regex(regex::Error),
+    /// The file changed on disk between [`open`](crate::Editor::open) and
+    /// [`save`](crate::Editor::save).
+    Conflict {
+        /// File that was modified externally.
+        path: PathBuf,
+    },
+    /// A three-way merge on [`save`](crate::Editor::save) found overlapping
+    /// changes (see [`merge_strategy`](crate::Editor::merge_strategy)).
+    MergeConflict {
+        /// File that was modified externally.
+        path: PathBuf,
+        /// The overlapping regions, in file order.
+        conflicts: Vec<MergeConflict>,
+    },
+    /// Another process holds the lock on the file.
+    Locked {
+        /// File that could not be locked.
+        path: PathBuf,
+        /// Contents of the sidecar (the holder's pid), when known.
+        holder: Option<String>,
+    },
+    /// No [checkpoint](crate::Editor::checkpoint) has this name.
+    UnknownCheckpoint {
+        /// The name that was looked up.
+        name: String,
+    },
+    /// A [`Journal`](crate::Journal) entry did not apply cleanly during
+    /// [`replay`](crate::Editor::replay).
+    ReplayMismatch {
+        /// Index of the offending entry.
+        entry: usize,
+        /// Its operation name.
+        op: String,
+    },
+    /// A patch passed to [`apply_patch`](crate::Editor::apply_patch) could not
+    /// be parsed.
+    InvalidPatch {
+        /// 1-based line in the patch text.
+        line: usize,
+        /// What was wrong.
+        reason: String,
+    },
+    /// The request cannot be carried out in the editor's current state.
+    Validation(String),
+}
+
+impl Error {
+    /// The closest [`io::ErrorKind`], as used when converting to `io::Error`.
+    pub fn kind(&self) -> io::ErrorKind {
+        match self {
+            Error::Io { source, .. } => source.kind(),
+            Error::Encoding { .. } | Error::MatchCount { .. } | Error::ReplayMismatch { .. } => {
+                io::ErrorKind::InvalidData
+            }
+            Error::NotFound { .. } | Error::UnknownCheckpoint { .. } => io::ErrorKind::NotFound,
+            #[cfg(feature = "regex")]
+            Error::Regex(_) => io::ErrorKind::InvalidInput,
+            Error::Conflict { .. } | Error::MergeConflict { .. } => io::ErrorKind::Other,
+            Error::Locked { .. } => io::ErrorKind::WouldBlock,
+            Error::InvalidPatch { .. } | Error::Validation(_) => io::ErrorKind::InvalidInput,
+        }
+    }
+
+    /// The file the error is about, if any.
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Error::Io { path, .. } | Error::Encoding { path, .. } => path.as_deref(),
+            Error::Conflict { path }
+            | Error::MergeConflict { path, .. }
+            | Error::Locked { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn encoding<E>(encoding: Encoding, source: E) -> Self
+    where
+        E: Into<Box<dyn error::Error + Send + Sync>>,
+    {
+        Error::Encoding {
+            path: None,
+            encoding,
+            source: source.into(),
+        }
+    }
+
+    pub(crate) fn validation(msg: impl Into<String>) -> Self {
+        Error::Validation(msg.into())
+    }
+
+    /// Attach `at` to an error that does not name a file yet.
+    fn with_path(mut self, at: &Path) -> Self {
+        if let Error::Io { path, .. } | Error::Encoding { path, .. } = &mut self {
+            path.get_or_insert_with(|| at.to_owned());
+        }
+        self
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(path) = self.path() {
+            write!(f, "{}: ", path.display())?;
+        }
+        match self {
+            Error::Io { source, .. } => write!(f, "{source}"),
+            Error::Encoding {
+                encoding, source, ..
+            } => write!(f, "invalid {encoding:?} text: {source}"),
+            Error::NotFound { marker } => write!(f, "marker not found: {marker:?}"),
+            Error::MatchCount {
+                pattern,
+                expected,
+                found,
+            } => write!(
+                f,
+                "pattern {pattern:?} matched {found} times, expected {expected}"
+            ),
+            #[cfg(feature = "regex")]
+            Error::Regex(e) => write!(f, "{e}"),
+            Error::Conflict { .. } => f.write_str("changed on disk since it was opened"),
+            Error::MergeConflict { conflicts, .. } => write!(
+                f,
+                "changed on disk; {} conflicting region(s) could not be merged",
+                conflicts.len()
+            ),
+            Error::Locked { holder, .. } => match holder {
+                Some(holder) => write!(f, "locked by process {holder}"),
+                None => f.write_str("locked"),
+            },
+            Error::UnknownCheckpoint { name } => write!(f, "no checkpoint named {name:?}"),
+            Error::ReplayMismatch { entry, op } => {
+                write!(f, "journal entry {entry} ({op}) does not match the buffer")
+            }
+            Error::InvalidPatch { line, reason } => {
+                write!(f, "invalid patch, line {line}: {reason}")
+            }
+            Error::Validation(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            Error::Encoding { source, .. } => Some(&**source),
+            #[cfg(feature = "regex")]
+            Error::Regex(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    /// Unwraps an `Error` previously converted into `io::Error`; anything
+    /// else becomes [`Error::Io`] without a path.
+    fn from(err: io::Error) -> Self {
+        if err.get_ref().is_some_and(|e| e.is::<Error>()) {
+            let inner = err.into_inner().expect("checked above");
+            return *inner.downcast::<Error>().expect("checked above");
+        }
+        Error::Io {
+            path: None,
+            source: err,
+        }
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::Io { path: None, source } => source,
+            err => io::Error::new(err.kind(), err),
+        }
+    }
+}
+
+#[cfg(feature = "regex")]
+impl From<regex::Error> for Error {
+    fn from(err: regex::Error) -> Self {
+        Error::Regex(err)
+    }
+}
+
+/// Attach a path to errors on their way out of the crate.
+pub(crate) trait Context<T> {
+    fn at(self, path: &Path) -> Result<T>;
+}
+
+impl<T, E: Into<Error>> Context<T> for std::result::Result<T, E> {
+    fn at(self, path: &Path) -> Result<T> {
+        self.map_err(|e| e.into().with_path(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_io_error() {
+        let err = Error::NotFound {
+            marker: "# END".into(),
+        };
+        let io: io::Error = err.into();
+        assert_eq!(io.kind(), io::ErrorKind::NotFound);
+        assert!(matches!(Error::from(io), Error::NotFound { marker } if marker == "# END"));
+
+        let plain = io::Error::from(io::ErrorKind::PermissionDenied);
+        let err = Err::<(), _>(plain).at(Path::new("x")).unwrap_err();
+        assert_eq!(err.to_string(), "x: permission denied");
+        let back = io::Error::from(err);
+        // `io::Error::source` delegates to the wrapped error's source
+        assert!(error::Error::source(&back).is_some_and(|e| e.is::<io::Error>()));
+    }
+}
@@ -0,0 +1,39 @@
+//! Match expectations for the fallible (`try_*`) editing methods.
+use std::fmt;
+
+/// How many matches a `try_*` pattern operation must find.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Expect {
+    /// Any number, including zero.
+    Any,
+    /// At least one match (default).
+    #[default]
+    Some,
+    /// At least `n` matches.
+    AtLeast(usize),
+    /// Exactly `n` matches.
+    Exactly(usize),
+}
+
+impl Expect {
+    /// Returns `true` if `found` matches satisfy this expectation.
+    pub fn accepts(self, found: usize) -> bool {
+        match self {
+            Expect::Any => true,
+            Expect::Some => found > 0,
+            Expect::AtLeast(n) => found >= n,
+            Expect::Exactly(n) => found == n,
+        }
+    }
+}
+
+impl fmt::Display for Expect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expect::Any => f.write_str("any number of"),
+            Expect::Some => f.write_str("at least 1"),
+            Expect::AtLeast(n) => write!(f, "at least {n}"),
+            Expect::Exactly(n) => write!(f, "exactly {n}"),
+        }
+    }
+}
@@ -0,0 +1,186 @@
+//! Bounded undo/redo of buffer edits, stored as reversible splices.
+use std::collections::VecDeque;
+
+/// `old` at byte `at` (in the buffer before the step) became `new`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Splice {
+    pub at: usize,
+    pub old: String,
+    pub new: String,
+}
+
+impl Splice {
+    /// Smallest splice turning `old` into `new` (common prefix and suffix
+    /// trimmed); `None` if they are equal.
+    pub fn between(old: &str, new: &str) -> Option<Self> {
+        if old == new {
+            return None;
+        }
+        let mut pre = old
+            .bytes()
+            .zip(new.bytes())
+            .take_while(|(a, b)| a == b)
+            .count();
+        while !(old.is_char_boundary(pre) && new.is_char_boundary(pre)) {
+            pre -= 1;
+        }
+        let mut suf = old[pre..]
+            .bytes()
+            .rev()
+            .zip(new[pre..].bytes().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+        while !(old.is_char_boundary(old.len() - suf) && new.is_char_boundary(new.len() - suf)) {
+            suf -= 1;
+        }
+        Some(Self {
+            at: pre,
+            old: old[pre..old.len() - suf].to_owned(),
+            new: new[pre..new.len() - suf].to_owned(),
+        })
+    }
+}
+
+/// One undoable call: its splices in ascending order.
+#[derive(Debug, Clone)]
+struct Step {
+    splices: Vec<Splice>,
+    /// Whether the buffer was dirty before the step.
+    was_dirty: bool,
+}
+
+impl Step {
+    fn undo(&self, buf: &mut String) {
+        // splices before the current one are already back in their old state,
+        // so `at` is valid as is
+        for s in &self.splices {
+            buf.replace_range(s.at..s.at + s.new.len(), &s.old);
+        }
+    }
+
+    fn redo(&self, buf: &mut String) {
+        for s in self.splices.iter().rev() {
+            buf.replace_range(s.at..s.at + s.old.len(), &s.new);
+        }
+    }
+}
+
+/// Undo and redo stacks, oldest step first.
+#[derive(Debug, Clone)]
+pub(crate) struct History {
+    undo: VecDeque<Step>,
+    redo: Vec<Step>,
+    limit: usize,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            limit: 100,
+        }
+    }
+}
+
+impl History {
+    /// Record a step (ignored if empty); forgets everything that could be redone.
+    pub fn record(&mut self, splices: Vec<Splice>, was_dirty: bool) {
+        if splices.is_empty() || self.limit == 0 {
+            return;
+        }
+        self.redo.clear();
+        self.undo.push_back(Step { splices, was_dirty });
+        while self.undo.len() > self.limit {
+            self.undo.pop_front();
+        }
+    }
+
+    /// Revert the newest step; returns the dirty flag from before it.
+    pub fn undo(&mut self, buf: &mut String) -> Option<bool> {
+        let step = self.undo.pop_back()?;
+        step.undo(buf);
+        let was_dirty = step.was_dirty;
+        self.redo.push(step);
+        Some(was_dirty)
+    }
+
+    /// Re-apply the most recently undone step.
+    pub fn redo(&mut self, buf: &mut String) -> bool {
+        let Some(step) = self.redo.pop() else {
+            return false;
+        };
+        step.redo(buf);
+        self.undo.push_back(step);
+        true
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// The buffer was saved: any undo or redo now diverges from the file.
+    pub fn saved(&mut self) {
+        for step in self.undo.iter_mut().chain(&mut self.redo) {
+            step.was_dirty = true;
+        }
+    }
+
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        while self.undo.len() > limit {
+            self.undo.pop_front();
+        }
+        if limit == 0 {
+            self.redo.clear();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splice_between_trims_common_ends() {
+        let s = Splice::between("a b  \nc\n", "a b\nc\n").unwrap();
+        assert_eq!((s.at, s.old.as_str(), s.new.as_str()), (3, "  ", ""));
+        assert!(Splice::between("x", "x").is_none());
+        // never splits a multi-byte character
+        let s = Splice::between("\u{e9}", "\u{e8}").unwrap();
+        assert_eq!((s.at, s.old.as_str()), (0, "\u{e9}"));
+    }
+
+    #[test]
+    fn multi_splice_step_round_trips() {
+        let mut buf = String::from("a-b-c");
+        let step = Step {
+            splices: vec![
+                Splice {
+                    at: 1,
+                    old: "-".into(),
+                    new: " + ".into(),
+                },
+                Splice {
+                    at: 3,
+                    old: "-".into(),
+                    new: "".into(),
+                },
+            ],
+            was_dirty: false,
+        };
+        step.redo(&mut buf);
+        assert_eq!(buf, "a + bc");
+        step.undo(&mut buf);
+        assert_eq!(buf, "a-b-c");
+    }
+}
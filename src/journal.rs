@@ -0,0 +1,124 @@
+//! Audit journal of buffer edits, serializable as JSON lines.
+use std::{ops::Range, time::SystemTime};
+
+#[cfg(feature = "serde")]
+use std::io::{self, BufRead, Write};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::history::Splice;
+
+/// Every change an [`Editor`](crate::Editor) made to its buffer while
+/// [`record_journal`](crate::Editor::record_journal) was on, oldest first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Journal {
+    entries: Vec<JournalEntry>,
+}
+
+/// One journaled operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct JournalEntry {
+    /// Operation name, e.g. `"replace"`, `"undo"` or `"normalize"`.
+    pub op: String,
+    /// Marker or pattern source the operation searched for.
+    pub pattern: Option<String>,
+    /// Text inserted or substituted, as passed by the caller.
+    pub text: Option<String>,
+    /// Byte ranges of the matches in the buffer after the operation.
+    pub matches: Vec<Range<usize>>,
+    /// The exact changes, in buffer order.
+    pub edits: Vec<JournalEdit>,
+    /// When the operation ran.
+    pub timestamp: SystemTime,
+    /// FNV-1a hash of the whole buffer after the operation.
+    pub checksum: u64,
+}
+
+/// `removed` at byte `at` (in the buffer before the operation) became `inserted`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct JournalEdit {
+    /// Byte offset in the buffer before the operation.
+    pub at: usize,
+    /// Text that was there.
+    pub removed: String,
+    /// Text that replaced it.
+    pub inserted: String,
+}
+
+impl From<Splice> for JournalEdit {
+    fn from(s: Splice) -> Self {
+        Self {
+            at: s.at,
+            removed: s.old,
+            inserted: s.new,
+        }
+    }
+}
+
+impl Journal {
+    /// The recorded entries, oldest first.
+    pub fn entries(&self) -> &[JournalEntry] {
+        &self.entries
+    }
+
+    /// Number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// `true` if nothing was recorded.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub(crate) fn push(&mut self, entry: JournalEntry) {
+        self.entries.push(entry);
+    }
+
+    pub(crate) fn truncate(&mut self, len: usize) {
+        self.entries.truncate(len);
+    }
+
+    /// Write one JSON object per entry, each on its own line.
+    #[cfg(feature = "serde")]
+    pub fn write_json_lines<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for entry in &self.entries {
+            serde_json::to_writer(&mut writer, entry)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()
+    }
+
+    /// Read a journal written by [`write_json_lines`](Journal::write_json_lines);
+    /// blank lines are skipped.
+    #[cfg(feature = "serde")]
+    pub fn read_json_lines<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut entries = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                entries.push(serde_json::from_str(&line)?);
+            }
+        }
+        Ok(Self { entries })
+    }
+}
+
+impl<'a> IntoIterator for &'a Journal {
+    type Item = &'a JournalEntry;
+    type IntoIter = std::slice::Iter<'a, JournalEntry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter()
+    }
+}
+
+/// 64-bit FNV-1a, stable across platforms and Rust versions.
+pub(crate) fn checksum(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |h, b| {
+        (h ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
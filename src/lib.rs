@@ -22,7 +22,7 @@
 //! Enable the feature below to pass a compiled `regex::Regex` anywhere a pattern is accepted.
 //!
 //! ```toml
-//! file-editor = { version = "0.2", features = ["regex"] }
+//! file-editor = { version = "0.3", features = ["regex"] }
 //! ```
 //!
 //! ```ignore
@@ -41,8 +41,48 @@
 //! ---
 //! **See [`Editor`] for the complete API and method-by-method examples.**
 
+mod anchor;
+mod atomic;
+mod backup;
+mod block;
+mod diff;
+mod edit;
 mod editor;
+mod encoding;
+mod error;
+mod expect;
+mod history;
+mod journal;
+mod line_ending;
+mod lock;
+mod marker;
+mod merge;
+mod metadata;
+mod normalize;
+mod patch;
 mod pattern;
+mod position;
+mod report;
+mod stamp;
 pub mod utils;
 
-pub use editor::Editor;
+pub use anchor::Anchor;
+pub use backup::Backup;
+pub use block::CommentStyle;
+pub use diff::DiffOptions;
+pub use editor::{Editor, EditorOptions, Scope};
+pub use encoding::Encoding;
+pub use error::{Error, Result};
+pub use expect::Expect;
+pub use journal::{Journal, JournalEdit, JournalEntry};
+pub use line_ending::{LineEnding, LineEndingPolicy};
+pub use lock::Lock;
+pub use marker::{Marker, Occurrence};
+pub use merge::{MergeConflict, MergeStrategy};
+pub use metadata::Preserve;
+pub use normalize::{Normalize, NormalizeReport};
+pub use patch::{HunkStatus, PatchOptions, PatchReport};
+pub use pattern::Pattern;
+pub use position::{Position, Span};
+pub use report::{Change, EditReport, SaveReport};
+pub use stamp::FileStamp;
@@ -0,0 +1,75 @@
+//! Line-ending detection and conversion (LF / CRLF).
+use std::borrow::Cow;
+
+/// A line terminator style.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    /// `\n` (Unix).
+    #[default]
+    Lf,
+    /// `\r\n` (Windows).
+    CrLf,
+}
+
+/// What [`save`](crate::Editor::save) does with the buffer's line endings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LineEndingPolicy {
+    /// Leave existing line endings as they are (default).
+    #[default]
+    Keep,
+    /// Convert every line ending to the given style.
+    Force(LineEnding),
+}
+
+impl LineEnding {
+    /// The terminator itself: `"\n"` or `"\r\n"`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+    }
+
+    /// The dominant line ending in `text`; [`Lf`](LineEnding::Lf) on ties.
+    ///
+    /// ```
+    /// use file_editor::LineEnding;
+    /// assert_eq!(LineEnding::detect("a\r\nb\r\nc\n"), LineEnding::CrLf);
+    /// assert_eq!(LineEnding::detect("no newline"), LineEnding::Lf);
+    /// ```
+    pub fn detect(text: &str) -> Self {
+        let crlf = text.matches("\r\n").count();
+        let lf = text.matches('\n').count() - crlf;
+        if crlf > lf {
+            LineEnding::CrLf
+        } else {
+            LineEnding::Lf
+        }
+    }
+
+    /// Convert every line ending in `text` to this style.
+    ///
+    /// ```
+    /// use file_editor::LineEnding;
+    /// assert_eq!(LineEnding::CrLf.apply("a\nb\r\n"), "a\r\nb\r\n");
+    /// assert_eq!(LineEnding::Lf.apply("a\r\nb\n"), "a\nb\n");
+    /// ```
+    pub fn apply(self, text: &str) -> Cow<'_, str> {
+        match self {
+            LineEnding::Lf if text.contains("\r\n") => text.replace("\r\n", "\n").into(),
+            LineEnding::CrLf if text.matches('\n').count() != text.matches("\r\n").count() => {
+                let mut out = String::with_capacity(text.len() + text.len() / 16);
+                let mut prev = '\0';
+                for c in text.chars() {
+                    if c == '\n' && prev != '\r' {
+                        out.push('\r');
+                    }
+                    out.push(c);
+                    prev = c;
+                }
+                out.into()
+            }
+            _ => text.into(),
+        }
+    }
+}
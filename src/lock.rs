@@ -0,0 +1,200 @@
+//! Advisory locking for the lifetime of an editing session.
+use std::{
+    fs::{self, File, OpenOptions, TryLockError},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process, thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::error::{Error, Result};
+
+/// How an [`Editor`](crate::Editor) locks its file; see
+/// [`EditorOptions::lock`](crate::EditorOptions::lock).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lock {
+    /// OS advisory lock on the file itself (`flock` on Unix, `LockFileEx` on
+    /// Windows). Cooperates with other tools using the same mechanism, e.g.
+    /// `flock(1)`. The file must exist.
+    Advisory,
+    /// Portable `<file>.lock` sidecar created exclusively and removed on
+    /// unlock. A sidecar older than `stale_after` (or, on Linux, one whose
+    /// owning process is gone) is considered abandoned and taken over.
+    Sidecar {
+        /// Age after which an existing sidecar is treated as stale.
+        ///
+        /// The age counts from when the lock was taken; the sidecar is not
+        /// refreshed while held, so this must exceed the longest session
+        /// that should keep the lock.
+        stale_after: Duration,
+    },
+}
+
+/// A held lock; released on drop.
+#[derive(Debug)]
+pub(crate) enum Guard {
+    Advisory(File),
+    Sidecar(PathBuf),
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        match self {
+            Guard::Advisory(file) => {
+                let _ = file.unlock();
+            }
+            Guard::Sidecar(path) => {
+                let _ = fs::remove_file(path);
+            }
+        }
+    }
+}
+
+/// Editor slot for a lock. Clones of an editor do **not** share the lock.
+#[derive(Debug, Default)]
+pub(crate) struct Held(pub Option<Guard>);
+
+impl Clone for Held {
+    fn clone(&self) -> Self {
+        Held(None)
+    }
+}
+
+const RETRY: Duration = Duration::from_millis(10);
+
+/// Lock `path`, retrying until `timeout` elapses; [`Error::Locked`] if it
+/// stays busy.
+pub(crate) fn acquire(path: &Path, kind: Lock, timeout: Duration) -> Result<Guard> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let holder = match kind {
+            Lock::Advisory => match try_advisory(path)? {
+                Some(guard) => return Ok(guard),
+                None => None,
+            },
+            Lock::Sidecar { stale_after } => match try_sidecar(path, stale_after)? {
+                Ok(guard) => return Ok(guard),
+                Err(holder) => holder,
+            },
+        };
+        if Instant::now() >= deadline {
+            return Err(Error::Locked {
+                path: path.to_owned(),
+                holder,
+            });
+        }
+        thread::sleep(RETRY);
+    }
+}
+
+/// `Ok(None)` means "held by someone else".
+fn try_advisory(path: &Path) -> io::Result<Option<Guard>> {
+    loop {
+        let file = OpenOptions::new().read(true).open(path)?;
+        match file.try_lock() {
+            // The file may have been atomically replaced while we waited;
+            // a lock on the old inode protects nothing.
+            Ok(()) if same_file(&file, path)? => return Ok(Some(Guard::Advisory(file))),
+            Ok(()) => continue,
+            Err(TryLockError::WouldBlock) => return Ok(None),
+            Err(TryLockError::Error(e)) => return Err(e),
+        }
+    }
+}
+
+/// Lock the freshly written `file` that is about to replace the original.
+pub(crate) fn relock(file: &File) -> io::Result<()> {
+    file.try_lock().map_err(io::Error::from)
+}
+
+/// `Ok(Err(holder))` means "held by someone else".
+fn try_sidecar(path: &Path, stale_after: Duration) -> io::Result<Result<Guard, Option<String>>> {
+    let lock_path = sidecar_path(path)?;
+    loop {
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&lock_path)
+        {
+            Ok(mut file) => {
+                writeln!(file, "{}", process::id())?;
+                return Ok(Ok(Guard::Sidecar(lock_path)));
+            }
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                let seen = Sidecar::read(&lock_path);
+                if !seen.is_stale(stale_after) {
+                    return Ok(Err(seen.holder));
+                }
+                // Another process may have taken the stale lock over since we
+                // looked; only remove the sidecar we judged.
+                if Sidecar::read(&lock_path) == seen {
+                    match fs::remove_file(&lock_path) {
+                        Ok(()) => {}
+                        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                        Err(e) => return Err(e),
+                    }
+                }
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn sidecar_path(path: &Path) -> io::Result<PathBuf> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?;
+    let mut name = name.to_owned();
+    name.push(".lock");
+    Ok(path.with_file_name(name))
+}
+
+/// An existing sidecar as observed at one moment.
+#[derive(Debug, PartialEq, Eq)]
+struct Sidecar {
+    /// Its contents: the holder's PID.
+    holder: Option<String>,
+    modified: Option<SystemTime>,
+}
+
+impl Sidecar {
+    fn read(lock_path: &Path) -> Self {
+        Sidecar {
+            holder: fs::read_to_string(lock_path)
+                .ok()
+                .map(|s| s.trim().to_owned()),
+            modified: fs::metadata(lock_path).and_then(|m| m.modified()).ok(),
+        }
+    }
+
+    fn is_stale(&self, stale_after: Duration) -> bool {
+        let too_old = self
+            .modified
+            .and_then(|t| t.elapsed().ok())
+            .is_some_and(|age| age > stale_after);
+        too_old || self.holder.as_deref().is_some_and(holder_is_gone)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn holder_is_gone(pid: &str) -> bool {
+    pid.parse::<u32>()
+        .is_ok_and(|pid| !Path::new(&format!("/proc/{pid}")).exists())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn holder_is_gone(_pid: &str) -> bool {
+    false
+}
+
+#[cfg(unix)]
+fn same_file(file: &File, path: &Path) -> io::Result<bool> {
+    use std::os::unix::fs::MetadataExt;
+    let (a, b) = (file.metadata()?, fs::metadata(path)?);
+    Ok(a.dev() == b.dev() && a.ino() == b.ino())
+}
+
+#[cfg(not(unix))]
+fn same_file(_file: &File, _path: &Path) -> io::Result<bool> {
+    Ok(true)
+}
@@ -0,0 +1,116 @@
+//! Which occurrences of a marker an operation acts on.
+use std::ops::Range;
+
+/// Selects occurrences of a marker, counted from 0 in buffer order.
+///
+/// Occurrences are found left to right and never overlap.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Occurrence {
+    /// The first occurrence (default).
+    #[default]
+    First,
+    /// The last occurrence.
+    Last,
+    /// The `n`-th occurrence, 0-based.
+    Nth(usize),
+    /// Every occurrence.
+    All,
+    /// Occurrences whose 0-based index lies in the range (an empty or
+    /// reversed range selects nothing).
+    Range(Range<usize>),
+}
+
+impl Occurrence {
+    /// Indices selected out of `total` occurrences.
+    fn select(&self, total: usize) -> Range<usize> {
+        match self {
+            Occurrence::First => 0..total.min(1),
+            Occurrence::Last => total.saturating_sub(1)..total,
+            Occurrence::Nth(n) => (*n).min(total)..n.saturating_add(1).min(total),
+            Occurrence::All => 0..total,
+            Occurrence::Range(r) => {
+                let end = r.end.min(total);
+                r.start.min(end)..end
+            }
+        }
+    }
+}
+
+/// A marker string plus the [`Occurrence`]s to act on.
+///
+/// Every marker-based operation takes `impl Into<Marker>`: a plain `&str`
+/// means its first occurrence, a `(&str, Occurrence)` pair picks others.
+///
+/// ```
+/// # use file_editor::{Editor, Occurrence};
+/// let mut ed = Editor::from_string("#[test]\nfn a() {}\n#[test]\nfn b() {}\n");
+/// ed.insert_after(("#[test]\n", Occurrence::All), "#[ignore]\n", false);
+/// assert_eq!(ed.as_str().matches("#[ignore]").count(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Marker<'a> {
+    /// The literal text to look for.
+    pub text: &'a str,
+    /// Which of its occurrences to use.
+    pub occurrence: Occurrence,
+}
+
+impl<'a> Marker<'a> {
+    /// Start offsets of the selected occurrences in `hay`.
+    pub(crate) fn positions(&self, hay: &str) -> Vec<usize> {
+        let all: Vec<usize> = hay.match_indices(self.text).map(|(i, _)| i).collect();
+        all[self.occurrence.select(all.len())].to_vec()
+    }
+}
+
+impl<'a> From<&'a str> for Marker<'a> {
+    fn from(text: &'a str) -> Self {
+        Self {
+            text,
+            occurrence: Occurrence::First,
+        }
+    }
+}
+
+impl<'a> From<&'a String> for Marker<'a> {
+    fn from(text: &'a String) -> Self {
+        Self::from(text.as_str())
+    }
+}
+
+impl<'a> From<(&'a str, Occurrence)> for Marker<'a> {
+    fn from((text, occurrence): (&'a str, Occurrence)) -> Self {
+        Self { text, occurrence }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn picks(occurrence: Occurrence) -> Vec<usize> {
+        Marker {
+            text: "ab",
+            occurrence,
+        }
+        .positions("ab-ab-ab-ab")
+    }
+
+    #[test]
+    fn selects_occurrences() {
+        assert_eq!(picks(Occurrence::First), [0]);
+        assert_eq!(picks(Occurrence::Last), [9]);
+        assert_eq!(picks(Occurrence::Nth(2)), [6]);
+        assert_eq!(picks(Occurrence::Nth(4)), Vec::<usize>::new());
+        assert_eq!(picks(Occurrence::All), [0, 3, 6, 9]);
+        assert_eq!(picks(Occurrence::Range(1..3)), [3, 6]);
+        assert_eq!(picks(Occurrence::Range(3..10)), [9]);
+    }
+
+    #[test]
+    fn degenerate_selectors_select_nothing() {
+        assert!(picks(Occurrence::Range(Range { start: 3, end: 1 })).is_empty());
+        assert!(picks(Occurrence::Range(7..9)).is_empty());
+        assert!(picks(Occurrence::Nth(usize::MAX)).is_empty());
+    }
+}
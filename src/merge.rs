@@ -0,0 +1,152 @@
+//! Line-based three-way merge (diff3) of the buffer with the file on disk.
+use crate::diff;
+
+/// What [`save`](crate::Editor::save) does when the file changed on disk since
+/// it was opened, see [`Editor::merge_strategy`](crate::Editor::merge_strategy).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Fail with [`Error::Conflict`](crate::Error::Conflict) (the default).
+    #[default]
+    Refuse,
+    /// Merge; overlapping changes are written into the buffer between
+    /// git-style `<<<<<<< ours` / `=======` / `>>>>>>> theirs` markers.
+    Markers,
+    /// Merge; overlapping changes leave the buffer untouched and are listed in
+    /// [`Error::MergeConflict`](crate::Error::MergeConflict).
+    Report,
+}
+
+/// A region both the buffer and the file on disk changed in different ways.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeConflict {
+    /// 1-based line of the common base where the region starts (for pure
+    /// insertions: the line they were inserted before).
+    pub line: usize,
+    /// The region as it was when the file was opened.
+    pub base: String,
+    /// The region in the buffer.
+    pub ours: String,
+    /// The region in the file on disk.
+    pub theirs: String,
+}
+
+/// Merge the changes `base → ours` and `base → theirs`.
+///
+/// Returns the merged text, with conflict markers (lines ending in `eol`)
+/// around every overlapping region, and the list of those regions.
+pub(crate) fn merge3(
+    base: &str,
+    ours: &str,
+    theirs: &str,
+    eol: &str,
+) -> (String, Vec<MergeConflict>) {
+    let o: Vec<&str> = base.split_inclusive('\n').collect();
+    let a: Vec<&str> = ours.split_inclusive('\n').collect();
+    let b: Vec<&str> = theirs.split_inclusive('\n').collect();
+    let ma = diff::matching(&o, &a);
+    let mb = diff::matching(&o, &b);
+
+    let mut out = String::with_capacity(ours.len().max(theirs.len()));
+    let mut conflicts = Vec::new();
+    let (mut i, mut j, mut k) = (0, 0, 0);
+    loop {
+        // lines unchanged on both sides
+        while i < o.len() && ma[i] == Some(j) && mb[i] == Some(k) {
+            out.push_str(o[i]);
+            i += 1;
+            j += 1;
+            k += 1;
+        }
+        // the next base line both sides kept ends the unstable chunk
+        let next = (i..o.len()).find_map(|n| Some((n, ma[n]?, mb[n]?)));
+        let (ni, nj, nk) = next.unwrap_or((o.len(), a.len(), b.len()));
+        let (old, mine, yours) = (&o[i..ni], &a[j..nj], &b[k..nk]);
+        if mine == old || mine == yours {
+            yours.iter().for_each(|l| out.push_str(l));
+        } else if yours == old {
+            mine.iter().for_each(|l| out.push_str(l));
+        } else {
+            out.push_str("<<<<<<< ours");
+            out.push_str(eol);
+            push_lines(&mut out, mine, eol);
+            out.push_str("=======");
+            out.push_str(eol);
+            push_lines(&mut out, yours, eol);
+            out.push_str(">>>>>>> theirs");
+            out.push_str(eol);
+            conflicts.push(MergeConflict {
+                line: i + 1,
+                base: old.concat(),
+                ours: mine.concat(),
+                theirs: yours.concat(),
+            });
+        }
+        if next.is_none() {
+            break;
+        }
+        (i, j, k) = (ni, nj, nk);
+    }
+    (out, conflicts)
+}
+
+/// Append `lines`, terminating the last one so a marker can follow.
+fn push_lines(out: &mut String, lines: &[&str], eol: &str) {
+    lines.iter().for_each(|l| out.push_str(l));
+    if !out.ends_with('\n') {
+        out.push_str(eol);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn merge(base: &str, ours: &str, theirs: &str) -> (String, usize) {
+        let (text, conflicts) = merge3(base, ours, theirs, "\n");
+        (text, conflicts.len())
+    }
+
+    #[test]
+    fn disjoint_changes_merge_cleanly() {
+        let base = "a\nb\nc\nd\ne\n";
+        let (text, n) = merge(base, "A\nb\nc\nd\ne\n", "a\nb\nc\nd\nE\nf\n");
+        assert_eq!((text.as_str(), n), ("A\nb\nc\nd\nE\nf\n", 0));
+    }
+
+    #[test]
+    fn identical_changes_are_not_conflicts() {
+        let (text, n) = merge("a\nb\n", "a\nX\n", "a\nX\n");
+        assert_eq!((text.as_str(), n), ("a\nX\n", 0));
+    }
+
+    #[test]
+    fn overlapping_changes_get_markers() {
+        let (text, conflicts) = merge3("a\nb\nc\n", "a\nours\nc\n", "a\ntheirs\nc\n", "\n");
+        assert_eq!(
+            text,
+            "a\n<<<<<<< ours\nours\n=======\ntheirs\n>>>>>>> theirs\nc\n"
+        );
+        assert_eq!(
+            conflicts,
+            [MergeConflict {
+                line: 2,
+                base: "b\n".into(),
+                ours: "ours\n".into(),
+                theirs: "theirs\n".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn deletion_next_to_edit_merges() {
+        let (text, n) = merge("a\nb\nc\nd\n", "a\nc\nd\n", "a\nb\nc\nD\n");
+        assert_eq!((text.as_str(), n), ("a\nc\nD\n", 0));
+    }
+
+    #[test]
+    fn unterminated_last_line_keeps_markers_on_their_own_lines() {
+        let (text, n) = merge("x", "y", "z");
+        assert_eq!(n, 1);
+        assert_eq!(text, "<<<<<<< ours\ny\n=======\nz\n>>>>>>> theirs\n");
+    }
+}
@@ -0,0 +1,166 @@
+//! Carrying file metadata (mode, owner, xattrs) and symlinks across atomic saves.
+use std::{
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+};
+
+/// Which properties of the original file survive a [`save`](crate::Editor::save).
+///
+/// Saves replace the file with a new one, so anything not copied here is
+/// reset to the defaults of a freshly created file. Everything is preserved
+/// by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Preserve {
+    /// Edit the file a symlink points to instead of replacing the link.
+    pub follow_symlinks: bool,
+    /// Copy permission bits (the read-only flag on Windows).
+    pub permissions: bool,
+    /// Copy owner and group (Unix only).
+    ///
+    /// Best effort: without the privilege to change the owner (or group),
+    /// the new file keeps the saving user's, and the save still succeeds.
+    pub ownership: bool,
+    /// Copy extended attributes (Unix, `features = ["xattr"]`).
+    ///
+    /// Best effort like `ownership`: attributes the user may not set, or
+    /// the file system does not support, are skipped.
+    pub xattrs: bool,
+}
+
+impl Default for Preserve {
+    fn default() -> Self {
+        Self {
+            follow_symlinks: true,
+            permissions: true,
+            ownership: true,
+            xattrs: true,
+        }
+    }
+}
+
+impl Preserve {
+    /// Preserve nothing: the saved file is a brand-new file at the same path.
+    pub fn none() -> Self {
+        Self {
+            follow_symlinks: false,
+            permissions: false,
+            ownership: false,
+            xattrs: false,
+        }
+    }
+}
+
+/// Follow `path` through any chain of symlinks to the file it names.
+///
+/// The result need not exist (dangling links resolve to their target).
+pub(crate) fn resolve(path: &Path) -> io::Result<PathBuf> {
+    let mut cur = path.to_owned();
+    for _ in 0..40 {
+        match fs::symlink_metadata(&cur) {
+            Ok(meta) if meta.file_type().is_symlink() => {
+                let target = fs::read_link(&cur)?;
+                cur = match cur.parent() {
+                    Some(dir) if target.is_relative() => dir.join(target),
+                    _ => target,
+                };
+            }
+            Ok(_) => return Ok(cur),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(cur),
+            Err(e) => return Err(e),
+        }
+    }
+    Err(io::Error::other(format!(
+        "too many levels of symbolic links: {}",
+        path.display()
+    )))
+}
+
+/// Copy the selected metadata of `original` (at `path`) onto `file`.
+#[cfg_attr(not(all(unix, feature = "xattr")), allow(unused_variables))]
+pub(crate) fn copy(
+    original: &fs::Metadata,
+    path: &Path,
+    file: &File,
+    preserve: &Preserve,
+) -> io::Result<()> {
+    #[cfg(unix)]
+    if preserve.ownership {
+        use std::os::unix::fs::MetadataExt;
+        let current = file.metadata()?;
+        if (current.uid(), current.gid()) != (original.uid(), original.gid()) {
+            chown_best_effort(file, original.uid(), original.gid())?;
+        }
+    }
+    // after chown, which may clear setuid/setgid bits
+    if preserve.permissions {
+        file.set_permissions(original.permissions())?;
+    }
+    #[cfg(all(unix, feature = "xattr"))]
+    if preserve.xattrs {
+        copy_xattrs(path, file)?;
+    }
+    Ok(())
+}
+
+/// Give `file` the owner and group `uid`:`gid` as far as the caller may.
+///
+/// Only root can give a file away, so an unprivileged save of someone
+/// else's file keeps the caller as owner and still tries to restore the
+/// group (allowed when the caller is a member of it).
+#[cfg(unix)]
+fn chown_best_effort(file: &File, uid: u32, gid: u32) -> io::Result<()> {
+    use std::os::unix::fs::fchown;
+    // `Ok(false)` when the chown was refused for lack of privilege
+    let applied = |r: io::Result<()>| match r {
+        Err(e) if e.kind() == io::ErrorKind::PermissionDenied => Ok(false),
+        r => r.map(|()| true),
+    };
+    if !applied(fchown(file, Some(uid), Some(gid)))? {
+        applied(fchown(file, None, Some(gid)))?;
+    }
+    Ok(())
+}
+
+#[cfg(all(unix, feature = "xattr"))]
+fn copy_xattrs(path: &Path, file: &File) -> io::Result<()> {
+    use xattr::FileExt;
+    let names = match xattr::list_deref(path) {
+        Ok(names) => names,
+        Err(e) if e.kind() == io::ErrorKind::Unsupported => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    for name in names {
+        let Some(value) = xattr::get_deref(path, &name)? else {
+            continue;
+        };
+        // e.g. `security.*` / `trusted.*` names an unprivileged user may not set
+        match file.set_xattr(&name, &value) {
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::PermissionDenied | io::ErrorKind::Unsupported
+                ) => {}
+            r => r?,
+        }
+    }
+    Ok(())
+}
+
+/// Rename `from` to `to`. A relative symlink moved to another directory is
+/// re-created with an absolute target so that it keeps pointing at its file.
+#[cfg_attr(not(unix), allow(unused_variables))]
+pub(crate) fn rename(from: &Path, to: &Path, follow_symlinks: bool) -> io::Result<()> {
+    #[cfg(unix)]
+    if follow_symlinks && fs::symlink_metadata(from)?.file_type().is_symlink() {
+        let target = fs::read_link(from)?;
+        let from_dir = std::path::absolute(from)?.parent().map(Path::to_path_buf);
+        let to_dir = std::path::absolute(to)?.parent().map(Path::to_path_buf);
+        if target.is_relative() && from_dir != to_dir {
+            let target = from_dir.unwrap_or_default().join(target);
+            std::os::unix::fs::symlink(target, to)?;
+            return fs::remove_file(from);
+        }
+    }
+    fs::rename(from, to)
+}
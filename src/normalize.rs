@@ -0,0 +1,115 @@
+//! Save-time whitespace normalization (final newline, trailing blanks).
+use crate::line_ending::LineEnding;
+
+/// Whitespace clean-up applied by [`save`](crate::Editor::save).
+///
+/// All switches are off by default; [`Normalize::editorconfig`] turns on
+/// everything `editorconfig-checker` enforces.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Normalize {
+    /// Make sure a non-empty file ends with a line ending.
+    pub final_newline: bool,
+    /// Remove blank lines at the end of the file.
+    pub trim_trailing_blank_lines: bool,
+    /// Strip spaces and tabs at the end of every line.
+    pub trim_trailing_whitespace: bool,
+}
+
+impl Normalize {
+    /// Exactly one trailing newline and no trailing whitespace anywhere.
+    pub fn editorconfig() -> Self {
+        Self {
+            final_newline: true,
+            trim_trailing_blank_lines: true,
+            trim_trailing_whitespace: true,
+        }
+    }
+}
+
+/// What a [`Normalize`] pass changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NormalizeReport {
+    /// Lines that lost trailing whitespace.
+    pub trimmed_lines: usize,
+    /// Blank lines removed from the end of the file.
+    pub removed_blank_lines: usize,
+    /// A final line ending was appended.
+    pub added_final_newline: bool,
+}
+
+impl NormalizeReport {
+    /// `true` if the pass modified the text.
+    pub fn changed(&self) -> bool {
+        self.trimmed_lines > 0 || self.removed_blank_lines > 0 || self.added_final_newline
+    }
+}
+
+/// Apply `policy` to `text`, terminating any added line with `eol`.
+pub(crate) fn apply(text: &str, policy: &Normalize, eol: LineEnding) -> (String, NormalizeReport) {
+    let mut report = NormalizeReport::default();
+    let mut lines: Vec<(&str, &str)> = text
+        .split_inclusive('\n')
+        .map(|line| {
+            let body = line.trim_end_matches(['\r', '\n']);
+            (body, &line[body.len()..])
+        })
+        .collect();
+
+    if policy.trim_trailing_whitespace {
+        for (body, _) in &mut lines {
+            let trimmed = body.trim_end_matches([' ', '\t']);
+            if trimmed.len() != body.len() {
+                *body = trimmed;
+                report.trimmed_lines += 1;
+            }
+        }
+    }
+
+    if policy.trim_trailing_blank_lines {
+        while lines.len() > 1 && is_blank(lines[lines.len() - 1].0) {
+            lines.pop();
+            report.removed_blank_lines += 1;
+        }
+        if lines.len() == 1 && is_blank(lines[0].0) {
+            lines.pop();
+            report.removed_blank_lines += 1;
+        }
+    }
+
+    let mut out: String = lines.iter().flat_map(|(b, t)| [*b, *t]).collect();
+
+    if policy.final_newline && !out.is_empty() && !out.ends_with('\n') {
+        out.push_str(eol.as_str());
+        report.added_final_newline = true;
+    }
+    (out, report)
+}
+
+fn is_blank(line: &str) -> bool {
+    line.trim_matches([' ', '\t']).is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(text: &str) -> (String, NormalizeReport) {
+        apply(text, &Normalize::editorconfig(), LineEnding::Lf)
+    }
+
+    #[test]
+    fn collapses_trailing_blank_lines() {
+        let (out, r) = run("a\nb  \n\n \n\n");
+        assert_eq!(out, "a\nb\n");
+        assert_eq!((r.trimmed_lines, r.removed_blank_lines), (2, 3));
+        assert!(!r.added_final_newline);
+    }
+
+    #[test]
+    fn adds_missing_newline_but_not_to_empty_text() {
+        assert_eq!(run("a").0, "a\n");
+        assert_eq!(run("").0, "");
+        assert_eq!(run("\n\n").0, "");
+        assert!(!run("ok\n").1.changed());
+    }
+}
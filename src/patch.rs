@@ -0,0 +1,306 @@
+//! Parsing unified diffs and locating their hunks, with offset and fuzz.
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+
+/// Tolerances for [`Editor::apply_patch_with`](crate::Editor::apply_patch_with).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchOptions {
+    /// Context lines that may be ignored at each end of a hunk (default 2,
+    /// like `patch`).
+    pub fuzz: usize,
+    /// How many lines away from its stated position a hunk may be found
+    /// (default unlimited).
+    pub max_offset: usize,
+    /// Write rejected hunks to `<file>.rej` next to the file (atomically,
+    /// and not at all in [`dry_run`](crate::Editor::dry_run) mode).
+    pub reject_file: bool,
+}
+
+impl Default for PatchOptions {
+    fn default() -> Self {
+        Self {
+            fuzz: 2,
+            max_offset: usize::MAX,
+            reject_file: false,
+        }
+    }
+}
+
+/// Outcome of one hunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HunkStatus {
+    /// Applied exactly where the hunk header said.
+    Applied,
+    /// Applied after moving it `offset` lines and/or ignoring `fuzz` context
+    /// lines at each end.
+    AppliedWithOffset {
+        /// Lines between the stated and the actual position.
+        offset: isize,
+        /// Context lines ignored at each end.
+        fuzz: usize,
+    },
+    /// Did not match anywhere; the buffer is unchanged for this hunk.
+    Rejected,
+}
+
+/// Per-hunk results of [`Editor::apply_patch`](crate::Editor::apply_patch).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchReport {
+    /// One status per hunk, in patch order.
+    pub hunks: Vec<HunkStatus>,
+    /// The `.rej` file written for rejected hunks, if any.
+    pub reject_file: Option<PathBuf>,
+}
+
+impl PatchReport {
+    /// Number of hunks that applied (with or without offset).
+    pub fn applied(&self) -> usize {
+        self.hunks.len() - self.rejected()
+    }
+
+    /// Number of rejected hunks.
+    pub fn rejected(&self) -> usize {
+        self.hunks
+            .iter()
+            .filter(|&&h| h == HunkStatus::Rejected)
+            .count()
+    }
+
+    /// `true` if every hunk applied exactly as stated.
+    pub fn is_clean(&self) -> bool {
+        self.hunks.iter().all(|&h| h == HunkStatus::Applied)
+    }
+}
+
+/// The changes a patch makes to one file.
+#[derive(Debug)]
+pub(crate) struct FilePatch {
+    pub old_path: String,
+    pub new_path: String,
+    pub hunks: Vec<Hunk>,
+}
+
+#[derive(Debug)]
+pub(crate) struct Hunk {
+    /// 0-based index of the first old line (for an empty old side: the line
+    /// the new lines go before).
+    pub old_start: usize,
+    pub lines: Vec<(Kind, String)>,
+    /// The hunk as it appeared in the patch, for `.rej` output.
+    pub raw: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Kind {
+    Context,
+    Remove,
+    Add,
+}
+
+/// A place where (a trimmed version of) a hunk matches.
+pub(crate) struct Found {
+    /// First matched line.
+    pub at: usize,
+    /// Number of lines replaced.
+    pub len: usize,
+    pub new: String,
+    pub offset: isize,
+    pub fuzz: usize,
+}
+
+/// Parse every file section of a unified diff.
+pub(crate) fn parse(text: &str) -> Result<Vec<FilePatch>> {
+    let lines: Vec<&str> = text.split_inclusive('\n').collect();
+    let mut files: Vec<FilePatch> = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let next = lines.get(i + 1).and_then(|l| l.strip_prefix("+++ "));
+        match (line.strip_prefix("--- "), next) {
+            (Some(old), Some(new)) => {
+                files.push(FilePatch {
+                    old_path: header_path(old),
+                    new_path: header_path(new),
+                    hunks: Vec::new(),
+                });
+                i += 2;
+            }
+            _ if line.starts_with("@@ ") => {
+                let Some(file) = files.last_mut() else {
+                    return Err(invalid(i, "hunk before any `---`/`+++` header"));
+                };
+                let (hunk, next) = parse_hunk(&lines, i)?;
+                file.hunks.push(hunk);
+                i = next;
+            }
+            _ => i += 1, // `diff --git`, `index …` and other noise
+        }
+    }
+    files.retain(|f| !f.hunks.is_empty());
+    if files.is_empty() {
+        return Err(invalid(0, "no hunks found"));
+    }
+    Ok(files)
+}
+
+fn parse_hunk(lines: &[&str], start: usize) -> Result<(Hunk, usize)> {
+    let header = lines[start];
+    let bad = || invalid(start, "malformed hunk header");
+    let spec = header[3..].split(" @@").next().ok_or_else(bad)?;
+    let mut parts = spec.split_whitespace();
+    let (old_start, mut old_left) = range(parts.next(), '-').ok_or_else(bad)?;
+    let (_, mut new_left) = range(parts.next(), '+').ok_or_else(bad)?;
+
+    let mut hunk = Hunk {
+        old_start: if old_left == 0 {
+            old_start
+        } else {
+            old_start.saturating_sub(1)
+        },
+        lines: Vec::new(),
+        raw: header.to_owned(),
+    };
+    let mut i = start + 1;
+    while old_left > 0 || new_left > 0 || lines.get(i).is_some_and(|l| l.starts_with('\\')) {
+        let Some(&line) = lines.get(i) else {
+            return Err(invalid(i, "hunk is shorter than its header says"));
+        };
+        hunk.raw.push_str(line);
+        let (kind, body) = match line.as_bytes().first() {
+            Some(b' ') => (Kind::Context, &line[1..]),
+            // some tools strip the space of empty context lines
+            Some(b'\n' | b'\r') => (Kind::Context, line),
+            Some(b'-') => (Kind::Remove, &line[1..]),
+            Some(b'+') => (Kind::Add, &line[1..]),
+            Some(b'\\') => {
+                // "\ No newline at end of file" applies to the previous line
+                if let Some((_, prev)) = hunk.lines.last_mut() {
+                    let trimmed = prev.trim_end_matches('\n').trim_end_matches('\r').len();
+                    prev.truncate(trimmed);
+                }
+                i += 1;
+                continue;
+            }
+            _ => return Err(invalid(i, "unexpected line inside hunk")),
+        };
+        let counts = match kind {
+            Kind::Context => old_left > 0 && new_left > 0,
+            Kind::Remove => old_left > 0,
+            Kind::Add => new_left > 0,
+        };
+        if !counts {
+            return Err(invalid(i, "hunk is longer than its header says"));
+        }
+        old_left -= usize::from(kind != Kind::Add);
+        new_left -= usize::from(kind != Kind::Remove);
+        hunk.lines.push((kind, body.to_owned()));
+        i += 1;
+    }
+    if !hunk.raw.ends_with('\n') {
+        hunk.raw.push('\n');
+    }
+    Ok((hunk, i))
+}
+
+/// `-12,3` → `(12, 3)`; a missing length means 1.
+fn range(spec: Option<&str>, sign: char) -> Option<(usize, usize)> {
+    let spec = spec?.strip_prefix(sign)?;
+    match spec.split_once(',') {
+        Some((start, len)) => Some((start.parse().ok()?, len.parse().ok()?)),
+        None => Some((spec.parse().ok()?, 1)),
+    }
+}
+
+/// File name from a `---`/`+++` line, without timestamp.
+fn header_path(rest: &str) -> String {
+    let rest = rest.trim_end_matches(['\n', '\r']);
+    rest.split('\t').next().unwrap_or(rest).trim().to_owned()
+}
+
+fn invalid(line: usize, reason: &str) -> Error {
+    Error::InvalidPatch {
+        line: line + 1,
+        reason: reason.to_owned(),
+    }
+}
+
+impl FilePatch {
+    /// `true` if this section is about `path` (ignoring `a/` / `b/` prefixes).
+    pub fn is_for(&self, path: &Path) -> bool {
+        [&self.new_path, &self.old_path]
+            .into_iter()
+            .filter(|p| p.as_str() != "/dev/null")
+            .any(|p| {
+                let p = p
+                    .strip_prefix("a/")
+                    .or_else(|| p.strip_prefix("b/"))
+                    .unwrap_or(p);
+                path.ends_with(p)
+            })
+    }
+}
+
+impl Hunk {
+    /// Find where this hunk applies in `buf` (split into lines), at or after
+    /// line `min`, preferring the stated position moved by `carry` lines.
+    pub fn locate(
+        &self,
+        buf: &[&str],
+        min: usize,
+        carry: isize,
+        opts: &PatchOptions,
+    ) -> Option<Found> {
+        let max_fuzz = opts
+            .fuzz
+            .min(self.context_len(false).max(self.context_len(true)));
+        for fuzz in 0..=max_fuzz {
+            let lead = fuzz.min(self.context_len(false));
+            let trail = fuzz.min(self.context_len(true));
+            let body = &self.lines[lead..self.lines.len() - trail];
+            let old: Vec<&str> = body
+                .iter()
+                .filter(|(k, _)| *k != Kind::Add)
+                .map(|(_, l)| l.as_str())
+                .collect();
+            let new: String = body
+                .iter()
+                .filter(|(k, _)| *k != Kind::Remove)
+                .map(|(_, l)| l.as_str())
+                .collect();
+
+            let stated = self.old_start + lead;
+            let expected = stated.saturating_add_signed(carry);
+            let fits = |at: usize| {
+                at >= min && at + old.len() <= buf.len() && buf[at..at + old.len()] == old[..]
+            };
+            for d in 0..=opts.max_offset {
+                let later = expected.checked_add(d).filter(|&at| at <= buf.len());
+                let earlier = expected.checked_sub(d).filter(|&at| at >= min);
+                if later.is_none() && earlier.is_none() {
+                    break;
+                }
+                if let Some(at) = [later, earlier].into_iter().flatten().find(|&at| fits(at)) {
+                    return Some(Found {
+                        at,
+                        len: old.len(),
+                        new,
+                        offset: at as isize - stated as isize,
+                        fuzz,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// Leading (or trailing) context lines.
+    fn context_len(&self, trailing: bool) -> usize {
+        let is_context = |(k, _): &&(Kind, String)| *k == Kind::Context;
+        if trailing {
+            self.lines.iter().rev().take_while(is_context).count()
+        } else {
+            self.lines.iter().take_while(is_context).count()
+        }
+    }
+}
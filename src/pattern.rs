@@ -1,8 +1,13 @@
 //! Pattern abstraction: literal `&str` or (with `regex`) compiled `Regex`.
+use std::ops::Range;
+
 #[cfg(feature = "regex")]
 use regex::Regex;
 
+use crate::edit::Edit;
+
 /// Pattern: either a substring literal or, if you enable the `regex` feature, a `Regex`.
+#[derive(Debug, Clone, Copy)]
 pub enum Pattern<'a> {
     /// Literal substring match.
     Literal(&'a str),
@@ -21,6 +26,33 @@ impl Pattern<'_> {
         }
     }
 
+    /// Number of (non-overlapping) matches in `hay`.
+    pub fn count(&self, hay: &str) -> usize {
+        match *self {
+            Pattern::Literal(s) => hay.matches(s).count(),
+            #[cfg(feature = "regex")]
+            Pattern::Re(re) => re.find_iter(hay).count(),
+        }
+    }
+
+    /// The literal text or the regex source.
+    pub fn as_str(&self) -> &str {
+        match *self {
+            Pattern::Literal(s) => s,
+            #[cfg(feature = "regex")]
+            Pattern::Re(re) => re.as_str(),
+        }
+    }
+
+    /// Byte range of the first match in `hay` starting at or after `from`.
+    pub(crate) fn find_at(&self, hay: &str, from: usize) -> Option<Range<usize>> {
+        match *self {
+            Pattern::Literal(s) => hay[from..].find(s).map(|i| from + i..from + i + s.len()),
+            #[cfg(feature = "regex")]
+            Pattern::Re(re) => re.find_at(hay, from).map(|m| m.range()),
+        }
+    }
+
     /// Replaces all matches in `buf` with `repl`, returning a new `String`.
     pub fn replace_all(&self, buf: &str, repl: &str) -> String {
         match *self {
@@ -29,6 +61,27 @@ impl Pattern<'_> {
             Pattern::Re(re) => re.replace_all(buf, repl).into(),
         }
     }
+
+    /// One edit per match, replacing it with `repl` (`$1`-style group
+    /// references are expanded for regexes, as in [`replace_all`](Pattern::replace_all)).
+    pub(crate) fn edits(&self, hay: &str, repl: &str) -> Vec<Edit> {
+        match *self {
+            Pattern::Literal(s) => hay
+                .match_indices(s)
+                .map(|(i, m)| Edit::new(i..i + m.len(), repl))
+                .collect(),
+            #[cfg(feature = "regex")]
+            Pattern::Re(re) => re
+                .captures_iter(hay)
+                .map(|caps| {
+                    let mut text = String::new();
+                    caps.expand(repl, &mut text);
+                    let m = caps.get(0).expect("group 0 always matches");
+                    Edit::new(m.range(), text)
+                })
+                .collect(),
+        }
+    }
 }
 
 /// Convert a `&str` into a `Pattern::Literal`.
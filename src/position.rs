@@ -0,0 +1,137 @@
+//! Line/column addressing and its mapping to byte offsets.
+use std::fmt;
+
+use crate::error::{Error, Result};
+
+/// A 1-based line and column in the buffer.
+///
+/// Columns count characters (Unicode scalar values), not bytes, as in
+/// compiler diagnostics. A line's last valid column is one past its last
+/// character, i.e. just before its line ending (`\n` or `\r\n`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Position {
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based character column.
+    pub column: usize,
+}
+
+impl Position {
+    /// Position at `line`, `column` (both 1-based).
+    pub fn new(line: usize, column: usize) -> Self {
+        Self { line, column }
+    }
+}
+
+impl From<(usize, usize)> for Position {
+    fn from((line, column): (usize, usize)) -> Self {
+        Self::new(line, column)
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// The text between two [`Position`]s; `end` is exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+    /// First character of the span.
+    pub start: Position,
+    /// Position just after the span.
+    pub end: Position,
+}
+
+impl Span {
+    /// Span from `start` up to (not including) `end`.
+    pub fn new(start: impl Into<Position>, end: impl Into<Position>) -> Self {
+        Self {
+            start: start.into(),
+            end: end.into(),
+        }
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+/// Byte offset of `pos` in `buf`.
+pub(crate) fn offset(buf: &str, pos: Position) -> Result<usize> {
+    let bad = || Error::validation(format!("position {pos} is outside the buffer"));
+    if pos.line == 0 || pos.column == 0 {
+        return Err(bad());
+    }
+    let mut start = 0;
+    let mut lines = buf.split('\n').peekable();
+    for _ in 1..pos.line {
+        start += lines.next().ok_or_else(bad)?.len() + 1;
+    }
+    let line = lines.next().ok_or_else(bad)?;
+    let content = match lines.peek() {
+        Some(_) => line.strip_suffix('\r').unwrap_or(line),
+        None => line,
+    };
+    let at = match content.char_indices().nth(pos.column - 1) {
+        Some((i, _)) => i,
+        None if content.chars().count() == pos.column - 1 => content.len(),
+        None => return Err(bad()),
+    };
+    Ok(start + at)
+}
+
+/// Line and column of byte `offset` in `buf`.
+///
+/// An offset inside a `\r\n` maps to the end of its line.
+pub(crate) fn position(buf: &str, offset: usize) -> Result<Position> {
+    if !buf.is_char_boundary(offset) {
+        return Err(Error::validation(format!(
+            "byte offset {offset} is outside the buffer or inside a character"
+        )));
+    }
+    let before = &buf[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let column = before[line_start..].trim_end_matches('\r').chars().count() + 1;
+    Ok(Position {
+        line: before.matches('\n').count() + 1,
+        column,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "ab\r\nçé\n\nx";
+
+    #[test]
+    fn offsets_respect_utf8_and_crlf() {
+        let at = |line, column| offset(TEXT, Position::new(line, column)).ok();
+        assert_eq!(at(1, 1), Some(0));
+        assert_eq!(at(1, 3), Some(2)); // before "\r\n"
+        assert_eq!(at(1, 4), None);
+        assert_eq!(at(2, 2), Some(6)); // 'é' after the two-byte 'ç'
+        assert_eq!(at(2, 3), Some(8));
+        assert_eq!(at(3, 1), Some(9));
+        assert_eq!(at(4, 2), Some(11));
+        assert_eq!(at(5, 1), None);
+        assert_eq!(at(0, 1), None);
+    }
+
+    #[test]
+    fn positions_round_trip() {
+        for (i, _) in TEXT.char_indices().chain([(TEXT.len(), ' ')]) {
+            let pos = position(TEXT, i).unwrap();
+            if !(TEXT[i..].starts_with('\n') && TEXT[..i].ends_with('\r')) {
+                assert_eq!(offset(TEXT, pos).unwrap(), i, "{pos}");
+            }
+        }
+        assert_eq!(position(TEXT, 3).unwrap(), Position::new(1, 3)); // the '\n' of "\r\n"
+        assert!(position(TEXT, 5).is_err()); // inside 'ç'
+        assert!(position(TEXT, 99).is_err());
+    }
+}
@@ -0,0 +1,52 @@
+//! Structured results of editor operations.
+use std::{
+    ops::{Range, RangeInclusive},
+    path::PathBuf,
+};
+
+use crate::normalize::NormalizeReport;
+
+/// Outcome of the last save, see [`Editor::last_save`](crate::Editor::last_save).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SaveReport {
+    /// `false` when the buffer was clean and nothing was written.
+    pub written: bool,
+    /// Destination file (`None` for [`save_to`](crate::Editor::save_to)).
+    pub path: Option<PathBuf>,
+    /// Number of encoded bytes written.
+    pub bytes: usize,
+    /// Backup made of the previous contents, if any.
+    pub backup: Option<PathBuf>,
+    /// Changes made by the [`Normalize`](crate::Normalize) policy.
+    pub normalized: NormalizeReport,
+    /// External changes were merged in first (see
+    /// [`merge_strategy`](crate::Editor::merge_strategy)).
+    pub merged: bool,
+}
+
+/// What one editing operation did, see [`Editor::last_report`](crate::Editor::last_report).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditReport {
+    /// Name of the operation, e.g. `"replace"` or `"insert_after"`.
+    pub op: &'static str,
+    /// One entry per match, in buffer order.
+    pub changes: Vec<Change>,
+    /// `false` when the buffer is byte-for-byte what it was before.
+    pub changed: bool,
+}
+
+impl EditReport {
+    /// Number of matches the operation acted on.
+    pub fn matches(&self) -> usize {
+        self.changes.len()
+    }
+}
+
+/// Where an edit landed in the buffer **after** the operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    /// Byte range of the inserted / replacement text (empty for deletions).
+    pub bytes: Range<usize>,
+    /// 1-based, inclusive line span covering `bytes`.
+    pub lines: RangeInclusive<usize>,
+}
@@ -0,0 +1,53 @@
+//! On-disk identity of a file, used to detect edits made behind our back.
+use std::{
+    collections::hash_map::DefaultHasher,
+    fs,
+    hash::{Hash, Hasher},
+    io,
+    path::Path,
+    time::SystemTime,
+};
+
+/// Snapshot of a file's identity: modification time, size and content hash.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileStamp {
+    /// Last modification time, when the platform reports one.
+    pub modified: Option<SystemTime>,
+    /// Size in bytes.
+    pub len: u64,
+    /// Hash of the raw contents.
+    pub hash: u64,
+}
+
+impl FileStamp {
+    /// Build a stamp from already-read `bytes` and the file's metadata.
+    pub(crate) fn new(meta: &fs::Metadata, bytes: &[u8]) -> Self {
+        Self {
+            modified: meta.modified().ok(),
+            len: bytes.len() as u64,
+            hash: hash(bytes),
+        }
+    }
+
+    /// Stamp the file at `path`; `Ok(None)` when it does not exist.
+    pub(crate) fn read(path: &Path) -> io::Result<Option<Self>> {
+        match fs::read(path) {
+            Ok(bytes) => Ok(Some(Self::new(&fs::metadata(path)?, &bytes))),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// `true` when both stamps describe the same contents.
+    ///
+    /// The modification time is ignored, so a bare `touch` is not a change.
+    pub fn same_contents(&self, other: &Self) -> bool {
+        self.len == other.len && self.hash == other.hash
+    }
+}
+
+fn hash(bytes: &[u8]) -> u64 {
+    let mut h = DefaultHasher::new();
+    bytes.hash(&mut h);
+    h.finish()
+}
@@ -0,0 +1,45 @@
+use file_editor::Editor;
+
+/// Saving replaces the file in one step and leaves no temp files behind.
+#[test]
+fn save_is_atomic_and_cleans_up() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("service.conf");
+    std::fs::write(&p, "port = 80\n")?;
+
+    Editor::open(&p)?.replace("80", "8080").save()?;
+
+    assert_eq!(std::fs::read_to_string(&p)?, "port = 8080\n");
+    let entries: Vec<_> = std::fs::read_dir(dir.path())?.collect();
+    assert_eq!(entries.len(), 1, "temp file must be renamed away");
+    Ok(())
+}
+
+#[test]
+fn durable_save_with_fsync() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("durable.txt");
+
+    Editor::create(&p)?.fsync(true).append("synced\n").save()?;
+
+    assert_eq!(std::fs::read_to_string(&p)?, "synced\n");
+    Ok(())
+}
+
+/// If the final rename fails, the temp file is removed again.
+#[test]
+fn failed_rename_removes_temp_file() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("file.txt");
+    std::fs::write(&p, "old")?;
+
+    let mut ed = Editor::open(&p)?;
+    std::fs::remove_file(&p)?;
+    std::fs::create_dir(&p)?; // rename onto a non-empty directory fails
+    std::fs::write(p.join("keep"), "")?;
+
+    assert!(ed.append(" new").save().is_err());
+    let entries: Vec<_> = std::fs::read_dir(dir.path())?.collect();
+    assert_eq!(entries.len(), 1);
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use file_editor::{Backup, Editor};
+
+#[test]
+fn simple_backup_keeps_previous_version() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("app.conf");
+    std::fs::write(&p, "v1\n")?;
+
+    let mut ed = Editor::open(&p)?;
+    ed.backup(Backup::Simple).replace("v1", "v2").save()?;
+    ed.replace("v2", "v3").save()?;
+
+    assert_eq!(std::fs::read_to_string(&p)?, "v3\n");
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("app.conf.bak"))?,
+        "v2\n"
+    );
+    assert_eq!(ed.backups()?, vec![dir.path().join("app.conf.bak")]);
+    Ok(())
+}
+
+#[test]
+fn numbered_backups_are_pruned() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("n.txt");
+    std::fs::write(&p, "0")?;
+
+    let mut ed = Editor::open(&p)?;
+    ed.backup(Backup::Numbered { keep: 2 });
+    for i in 1..=4 {
+        ed.replace((i - 1).to_string().as_str(), &i.to_string())
+            .save()?;
+    }
+
+    let names: Vec<_> = ed
+        .backups()?
+        .iter()
+        .map(|b| b.file_name().unwrap().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(names, ["n.txt.~3~", "n.txt.~4~"]);
+    assert_eq!(std::fs::read_to_string(dir.path().join("n.txt.~4~"))?, "3");
+    Ok(())
+}
+
+#[test]
+fn timestamped_backups_can_be_restored() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let baks = dir.path().join("backups");
+    let p = dir.path().join("t.txt");
+    std::fs::write(&p, "first")?;
+
+    let mut ed = Editor::open(&p)?;
+    ed.backup(Backup::Timestamped {
+        dir: baks.clone(),
+        keep: 0,
+    });
+    ed.replace("first", "second").save()?;
+    ed.replace("second", "third").save()?;
+
+    let list = ed.backups()?;
+    assert_eq!(list.len(), 2);
+    assert!(list.iter().all(|b| b.starts_with(&baks)));
+    assert_eq!(std::fs::read_to_string(&list[0])?, "first");
+
+    ed.restore_backup(&list[0])?.save()?;
+    assert_eq!(std::fs::read_to_string(&p)?, "first");
+    assert_eq!(ed.backups()?.len(), 3);
+    Ok(())
+}
+
+#[test]
+fn no_backup_by_default() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("plain.txt");
+    Editor::create(&p)?.append("x").save()?;
+
+    assert!(Editor::open(&p)?.backups()?.is_empty());
+    assert_eq!(std::fs::read_dir(dir.path())?.count(), 1);
+    Ok(())
+}
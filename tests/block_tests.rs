@@ -0,0 +1,91 @@
+use file_editor::{Anchor, CommentStyle, Editor, Error};
+
+#[test]
+fn repeated_runs_are_idempotent() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("sshd_config");
+    std::fs::write(&p, "Port 22\nPermitRootLogin yes\n")?;
+
+    for _ in 0..3 {
+        Editor::open(&p)?
+            .ensure_block("hardening", "PasswordAuthentication no\n", Anchor::End)?
+            .save()?;
+    }
+    assert_eq!(
+        std::fs::read_to_string(&p)?,
+        "Port 22\nPermitRootLogin yes\n# BEGIN hardening\nPasswordAuthentication no\n# END hardening\n"
+    );
+
+    // an unchanged block leaves the buffer clean, so nothing is written
+    let mut ed = Editor::open(&p)?;
+    ed.ensure_block("hardening", "PasswordAuthentication no", Anchor::End)?
+        .save()?;
+    assert!(!ed.last_report().unwrap().changed);
+    assert!(!ed.last_save().unwrap().written);
+    Ok(())
+}
+
+#[test]
+fn body_is_replaced_in_place() -> std::io::Result<()> {
+    let mut ed = Editor::from_string("a\n# BEGIN x\nold\n# END x\nb\n");
+    ed.ensure_block("x", "new 1\nnew 2\n", Anchor::Start)?;
+    assert_eq!(ed.as_str(), "a\n# BEGIN x\nnew 1\nnew 2\n# END x\nb\n");
+    assert!(ed.has_block("x"));
+
+    ed.remove_block("x")?;
+    assert_eq!(ed.as_str(), "a\nb\n");
+    assert!(!ed.has_block("x"));
+    ed.remove_block("x")?;
+    assert!(!ed.last_report().unwrap().changed);
+    Ok(())
+}
+
+#[test]
+fn anchors_and_line_endings() -> std::io::Result<()> {
+    let mut ed = Editor::from_string("[a]\r\nk = 1\r\n[b]\r\nk = 2");
+    ed.ensure_block("one", "x = 1", Anchor::After("[a]".into()))?
+        .ensure_block("two", "y = 2", Anchor::Before("[b]".into()))?
+        .ensure_block("three", "z = 3", Anchor::End)?
+        .ensure_block("zero", "w = 0", Anchor::Start)?;
+    assert_eq!(
+        ed.as_str(),
+        "# BEGIN zero\r\nw = 0\r\n# END zero\r\n\
+         [a]\r\n# BEGIN one\r\nx = 1\r\n# END one\r\nk = 1\r\n\
+         # BEGIN two\r\ny = 2\r\n# END two\r\n[b]\r\nk = 2\r\n\
+         # BEGIN three\r\nz = 3\r\n# END three\r\n"
+    );
+
+    // a missing anchor falls back to the end
+    let mut ed = Editor::from_string("x\n");
+    ed.ensure_block("b", "y\n", Anchor::After("nope".into()))?;
+    assert_eq!(ed.as_str(), "x\n# BEGIN b\ny\n# END b\n");
+    Ok(())
+}
+
+#[test]
+fn custom_comment_style() -> std::io::Result<()> {
+    let mut ed = Editor::from_string("body { }\n");
+    ed.comment_style(CommentStyle::c()).ensure_block(
+        "theme",
+        ".dark { color: #fff; }\n",
+        Anchor::End,
+    )?;
+    assert!(
+        ed.as_str()
+            .ends_with("/* BEGIN theme */\n.dark { color: #fff; }\n/* END theme */\n")
+    );
+
+    // markers written in another style are not recognised
+    ed.comment_style(CommentStyle::new(";", ""));
+    assert!(!ed.has_block("theme"));
+    Ok(())
+}
+
+#[test]
+fn unterminated_block_is_an_error() {
+    let mut ed = Editor::from_string("# BEGIN x\nrest of file\n");
+    let err = ed.ensure_block("x", "y\n", Anchor::End).unwrap_err();
+    assert!(matches!(err, Error::Validation(_)));
+    assert!(ed.remove_block("x").is_err());
+    assert_eq!(ed.as_str(), "# BEGIN x\nrest of file\n");
+}
@@ -0,0 +1,75 @@
+use file_editor::{Editor, Error, Expect};
+
+#[test]
+fn rollback_and_release() -> file_editor::Result<()> {
+    let mut ed = Editor::from_string("[package]\n");
+    ed.checkpoint("start")
+        .append("[deps]\n")
+        .checkpoint("before-deps")
+        .append("serde = \"1\"\n")
+        .checkpoint("after-deps");
+    assert_eq!(ed.checkpoints(), ["start", "before-deps", "after-deps"]);
+
+    ed.rollback_to("before-deps")?;
+    assert_eq!(ed.as_str(), "[package]\n[deps]\n");
+    assert_eq!(ed.checkpoints(), ["start", "before-deps"]);
+
+    // rollback is one undoable step
+    assert!(ed.undo());
+    assert!(ed.as_str().ends_with("serde = \"1\"\n"));
+
+    ed.release("before-deps")?;
+    assert_eq!(ed.checkpoints(), ["start"]);
+    let err = ed.rollback_to("after-deps").unwrap_err();
+    assert!(matches!(&err, Error::UnknownCheckpoint { name } if name == "after-deps"));
+    Ok(())
+}
+
+#[test]
+fn failed_transaction_restores_everything() {
+    let mut ed = Editor::from_string("a = 1\n");
+    ed.append("b = 2\n");
+    let res: Result<(), Error> = ed.transaction(|e| {
+        e.replace("1", "10").checkpoint("inner");
+        e.try_erase("c = 3", Expect::Exactly(1))?;
+        Ok(())
+    });
+    assert!(matches!(res, Err(Error::MatchCount { .. })));
+    assert_eq!(ed.as_str(), "a = 1\nb = 2\n");
+    assert_eq!(ed.reports().len(), 1);
+    assert!(ed.checkpoints().is_empty());
+    assert!(!ed.can_redo());
+    assert!(ed.undo());
+    assert_eq!(ed.as_str(), "a = 1\n");
+}
+
+#[test]
+fn successful_transaction_keeps_edits() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("f.txt");
+    std::fs::write(&p, "x\n")?;
+    let mut ed = Editor::open(&p)?;
+    let n = ed.transaction(|e| -> std::io::Result<usize> {
+        e.try_replace("x", "y", Expect::Any)?;
+        Ok(e.last_report().unwrap().matches())
+    })?;
+    assert_eq!(n, 1);
+    ed.save()?;
+    assert_eq!(std::fs::read_to_string(&p)?, "y\n");
+    Ok(())
+}
+
+#[test]
+fn failed_transaction_restores_released_checkpoints() -> file_editor::Result<()> {
+    let mut ed = Editor::from_string("a\n");
+    ed.checkpoint("outer").append("b\n").checkpoint("inner");
+    let res = ed.transaction(|e| {
+        e.release("outer")?;
+        e.rollback_to("missing").map(drop)
+    });
+    assert!(matches!(res, Err(Error::UnknownCheckpoint { .. })));
+    assert_eq!(ed.checkpoints(), ["outer", "inner"]);
+    ed.rollback_to("outer")?;
+    assert_eq!(ed.as_str(), "a\n");
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use file_editor::{Editor, Error};
+
+#[test]
+fn save_detects_external_change() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("shared.conf");
+    std::fs::write(&p, "a = 1\n")?;
+
+    let mut ed = Editor::open(&p)?;
+    std::fs::write(&p, "a = 1\nb = 2\n")?; // someone else edits
+
+    let err = ed.append("c = 3\n").save().unwrap_err();
+    assert!(matches!(&err, Error::Conflict { path } if *path == p));
+    assert!(err.to_string().contains("changed on disk"));
+    assert_eq!(std::fs::read_to_string(&p)?, "a = 1\nb = 2\n");
+    Ok(())
+}
+
+#[test]
+fn save_force_overwrites_external_change() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("force.txt");
+    std::fs::write(&p, "ours")?;
+
+    let mut ed = Editor::open(&p)?;
+    std::fs::write(&p, "theirs")?;
+    ed.append("!").save_force()?;
+    assert_eq!(std::fs::read_to_string(&p)?, "ours!");
+
+    // the editor is in sync again, so a plain save works
+    ed.append("?").save()?;
+    assert_eq!(std::fs::read_to_string(&p)?, "ours!?");
+    Ok(())
+}
+
+#[test]
+fn reload_discards_buffer() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("reload.txt");
+    std::fs::write(&p, "one\n")?;
+
+    let mut ed = Editor::open(&p)?;
+    std::fs::write(&p, "two\n")?;
+    ed.append("lost\n").reload()?.append("three\n").save()?;
+
+    assert_eq!(std::fs::read_to_string(&p)?, "two\nthree\n");
+    Ok(())
+}
+
+#[test]
+fn deleted_file_is_a_conflict_but_touch_is_not() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("touch.txt");
+    std::fs::write(&p, "x")?;
+
+    let mut ed = Editor::open(&p)?;
+    let stamp = ed.stamp().unwrap().clone();
+    assert_eq!(stamp.len, 1);
+
+    // rewrite identical contents: only the mtime moves
+    std::fs::write(&p, "x")?;
+    ed.append("y").save()?;
+    assert_eq!(std::fs::read_to_string(&p)?, "xy");
+
+    std::fs::remove_file(&p)?;
+    assert!(matches!(
+        ed.append("z").save().unwrap_err(),
+        Error::Conflict { .. }
+    ));
+    Ok(())
+}
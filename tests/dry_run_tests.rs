@@ -0,0 +1,132 @@
+use file_editor::{DiffOptions, Editor, EditorOptions, Normalize};
+
+#[test]
+fn dry_run_save_writes_nothing() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("conf.ini");
+    std::fs::write(&p, "a = 1\nb = 2\n")?;
+    let before = std::fs::metadata(&p)?.modified()?;
+
+    let mut ed = Editor::open(&p)?;
+    ed.dry_run(true).replace("b = 2", "b = 3").save()?;
+    ed.save_as(dir.path().join("copy.ini"))?;
+
+    assert_eq!(std::fs::read_to_string(&p)?, "a = 1\nb = 2\n");
+    assert_eq!(std::fs::metadata(&p)?.modified()?, before);
+    assert!(!dir.path().join("copy.ini").exists());
+    assert_eq!(ed.path(), Some(p.as_path()));
+    let report = ed.last_save().unwrap();
+    assert!(!report.written);
+    assert_eq!(report.bytes, 12);
+
+    // switching dry-run off saves the pending edit
+    ed.dry_run(false).save()?;
+    assert_eq!(std::fs::read_to_string(&p)?, "a = 1\nb = 3\n");
+    assert_eq!(ed.diff(), "");
+    Ok(())
+}
+
+#[test]
+fn diff_against_contents_at_open() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("notes.txt");
+    std::fs::write(&p, "one\ntwo\nthree")?;
+
+    let mut ed = Editor::open(&p)?;
+    ed.dry_run(true)
+        .normalize(Normalize::editorconfig())
+        .prepend("zero\n")
+        .save()?;
+
+    let diff = ed.diff_with(&DiffOptions {
+        context: 1,
+        color: false,
+    });
+    let name = p.to_string_lossy().trim_start_matches('/').to_owned();
+    assert_eq!(
+        diff,
+        format!(
+            "--- a/{name}\n+++ b/{name}\n\
+             @@ -1,3 +1,4 @@\n+zero\n one\n two\n-three\n\\ No newline at end of file\n+three\n"
+        )
+    );
+    Ok(())
+}
+
+#[test]
+fn new_file_diffs_from_dev_null() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let mut ed = EditorOptions::new()
+        .create(true)
+        .lazy(true)
+        .open(dir.path().join("new.txt"))?;
+    ed.append("hello\n");
+    let diff = ed.diff();
+    assert!(diff.starts_with("--- /dev/null\n"));
+    assert!(diff.ends_with("@@ -0,0 +1 @@\n+hello\n"));
+    Ok(())
+}
+
+#[test]
+fn colored_diff_wraps_lines_in_escapes() {
+    let mut ed = Editor::from_string("x\n");
+    ed.replace("x", "y");
+    let diff = ed.diff_with(&DiffOptions {
+        color: true,
+        ..DiffOptions::default()
+    });
+    assert!(diff.contains("\x1b[31m-x\x1b[0m\n"));
+    assert!(diff.contains("\x1b[32m+y\x1b[0m\n"));
+    assert!(diff.contains("\x1b[36m@@ -1 +1 @@\x1b[0m\n"));
+}
+
+#[test]
+fn diff_headers_resolve_dot_components() -> std::io::Result<()> {
+    // lazily created files: nothing is touched on disk
+    let header = |path: &str| -> std::io::Result<String> {
+        let mut ed = EditorOptions::new().create(true).lazy(true).open(path)?;
+        ed.append("x\n");
+        Ok(ed.diff().lines().nth(1).unwrap_or_default().to_owned())
+    };
+    assert_eq!(header("./x.txt")?, "+++ b/x.txt");
+    assert_eq!(header("sub/../up.txt")?, "+++ b/up.txt");
+    assert_eq!(header("../outside/x.txt")?, "+++ b/x.txt");
+
+    let dir = tempfile::tempdir()?;
+    std::fs::create_dir(dir.path().join("sub"))?;
+    let p = dir.path().join("sub").join("..").join("up.txt");
+    std::fs::write(&p, "a\n")?;
+    let mut ed = Editor::open(&p)?;
+    ed.replace("a", "b");
+    let name = dir.path().join("up.txt");
+    let name = name.to_string_lossy().trim_start_matches('/').to_owned();
+    assert!(
+        ed.diff()
+            .starts_with(&format!("--- a/{name}\n+++ b/{name}\n"))
+    );
+    Ok(())
+}
+
+#[test]
+fn large_rewrites_diff_in_bounded_memory() {
+    let old: String = (0..10_000).map(|i| format!("line {i}\n")).collect();
+
+    let mut ed = Editor::from_string(&old);
+    ed.replace("line", "row");
+    let diff = ed.diff();
+    assert!(diff.contains("@@ -1,10000 +1,10000 @@\n"));
+    assert_eq!(diff.lines().filter(|l| l.starts_with('-')).count(), 10_001);
+
+    // every tenth line kept
+    let old: String = (0..10_000)
+        .map(|i| match i % 10 {
+            0 => format!("keep {i}\n"),
+            _ => format!("line {i}\n"),
+        })
+        .collect();
+    let mut ed = Editor::from_string(old);
+    ed.replace("line", "row");
+    let diff = ed.diff();
+    assert_eq!(diff.lines().filter(|l| l.starts_with('+')).count(), 9_001);
+    assert_eq!(diff.lines().filter(|l| l.starts_with(' ')).count(), 1_000);
+}
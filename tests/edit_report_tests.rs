@@ -0,0 +1,56 @@
+use file_editor::Editor;
+
+#[test]
+fn replace_reports_matches_and_ranges() {
+    let mut ed = Editor::from_string("a=1\nb=2\nc=1\n");
+    ed.replace("=1", "=10");
+    let r = ed.last_report().unwrap();
+    assert_eq!(r.op, "replace");
+    assert!(r.changed);
+    assert_eq!(r.matches(), 2);
+    assert_eq!(r.changes[0].bytes, 1..4);
+    assert_eq!(r.changes[1].lines, 3..=3);
+    assert_eq!(&ed.as_str()[r.changes[1].bytes.clone()], "=10");
+}
+
+#[test]
+fn multiline_insert_spans_lines() {
+    let mut ed = Editor::from_string("[a]\n[b]\n");
+    ed.insert_after("[a]", "x = 1\ny = 2\n", false);
+    let r = ed.last_report().unwrap();
+    assert_eq!(r.changes[0].lines, 2..=3);
+    ed.erase("[b]\n");
+    assert_eq!(ed.last_report().unwrap().changes[0].bytes, 16..16);
+    assert_eq!(ed.reports().len(), 2);
+}
+
+#[test]
+fn no_op_edits_leave_the_file_alone() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("f.txt");
+    std::fs::write(&p, "same\n")?;
+
+    let mut ed = Editor::open(&p)?;
+    ed.erase("missing")
+        .replace("same", "same")
+        .mask("nope", "*");
+    assert!(ed.reports().iter().all(|r| !r.changed));
+    assert_eq!(ed.reports()[1].matches(), 1);
+    ed.save()?;
+    assert!(!ed.last_save().unwrap().written);
+
+    ed.append("more\n").save()?;
+    assert!(ed.last_save().unwrap().written);
+    assert!(ed.reports().is_empty());
+    Ok(())
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn regex_replacement_expands_groups() {
+    let re = regex::Regex::new(r"(\w+)=(\d)").unwrap();
+    let mut ed = Editor::from_string("a=1 b=2");
+    ed.replace(&re, "$2=$1");
+    assert_eq!(ed.as_str(), "1=a 2=b");
+    assert_eq!(ed.last_report().unwrap().changes[1].bytes, 4..7);
+}
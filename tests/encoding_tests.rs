@@ -0,0 +1,79 @@
+use file_editor::{Editor, Encoding};
+
+#[test]
+fn utf8_bom_is_hidden_and_kept() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("bom.csproj");
+    std::fs::write(&p, "\u{feff}<Project>\n")?;
+
+    let mut ed = Editor::open(&p)?;
+    assert!(ed.has_bom());
+    assert_eq!(ed.find_lines("<Project>", None), vec![1]);
+    ed.prepend("<?xml version=\"1.0\"?>\n").save()?;
+
+    assert_eq!(
+        std::fs::read(&p)?,
+        b"\xEF\xBB\xBF<?xml version=\"1.0\"?>\n<Project>\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn utf16_round_trips() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("utf16.txt");
+    let mut bytes = vec![0xFF, 0xFE];
+    "key=old\n"
+        .encode_utf16()
+        .for_each(|u| bytes.extend(u.to_le_bytes()));
+    std::fs::write(&p, &bytes)?;
+
+    let mut ed = Editor::open(&p)?;
+    assert_eq!(ed.encoding(), Encoding::Utf16Le);
+    ed.replace("old", "new").save()?;
+
+    let mut expected = vec![0xFF, 0xFE];
+    "key=new\n"
+        .encode_utf16()
+        .for_each(|u| expected.extend(u.to_le_bytes()));
+    assert_eq!(std::fs::read(&p)?, expected);
+    Ok(())
+}
+
+#[test]
+fn explicit_encoding_and_transcode() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("be.txt");
+    std::fs::write(&p, b"\x00h\x00i")?; // UTF-16BE without BOM
+
+    let mut ed = Editor::open_with_encoding(&p, Encoding::Utf16Be)?;
+    assert!(!ed.has_bom());
+    ed.transcode(Encoding::Utf8, true).save()?;
+    assert_eq!(std::fs::read(&p)?, b"\xEF\xBB\xBFhi");
+    Ok(())
+}
+
+#[test]
+fn invalid_utf8_is_an_error() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("bad.txt");
+    std::fs::write(&p, b"\xC3\x28")?;
+
+    let err = Editor::open(&p).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    Ok(())
+}
+
+#[cfg(feature = "legacy-encodings")]
+#[test]
+fn windows_1252_round_trips() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("legacy.ini");
+    std::fs::write(&p, b"name=caf\xE9\n")?;
+
+    Editor::open_with_encoding(&p, Encoding::Windows1252)?
+        .append("price=5\u{20AC}\n")
+        .save()?;
+    assert_eq!(std::fs::read(&p)?, b"name=caf\xE9\nprice=5\x80\n");
+    Ok(())
+}
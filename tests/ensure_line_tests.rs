@@ -0,0 +1,83 @@
+use file_editor::{Anchor, Editor};
+
+#[test]
+fn converges_on_repeated_runs() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("sysctl.conf");
+    std::fs::write(
+        &p,
+        "# kernel\nnet.ipv4.ip_forward = 0\nvm.swappiness = 60\n",
+    )?;
+
+    let run = || -> std::io::Result<bool> {
+        let mut ed = Editor::open(&p)?;
+        ed.ensure_line_matching("ip_forward", "net.ipv4.ip_forward = 1", Anchor::End)
+            .ensure_line("fs.file-max = 100000")
+            .ensure_absent("swappiness");
+        let changed = ed.reports().iter().any(|r| r.changed);
+        ed.save()?;
+        Ok(changed)
+    };
+    assert!(run()?);
+    assert!(!run()?);
+    assert_eq!(
+        std::fs::read_to_string(&p)?,
+        "# kernel\nnet.ipv4.ip_forward = 1\nfs.file-max = 100000\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn ensure_line_matches_whole_lines() {
+    let mut ed = Editor::from_string("alias ll='ls -l'\r\nalias l='ls'");
+    ed.ensure_line("alias l='ls'");
+    assert!(!ed.last_report().unwrap().changed);
+
+    // a substring of another line does not count
+    ed.ensure_line("alias l");
+    assert_eq!(
+        ed.as_str(),
+        "alias ll='ls -l'\r\nalias l='ls'\r\nalias l\r\n"
+    );
+}
+
+#[test]
+fn only_first_match_is_replaced_and_anchor_is_used_otherwise() {
+    let mut ed = Editor::from_string("[a]\nx = 1\n[b]\nx = 2\n");
+    ed.ensure_line_matching("x =", "x = 9", Anchor::End);
+    assert_eq!(ed.as_str(), "[a]\nx = 9\n[b]\nx = 2\n");
+    assert_eq!(ed.last_report().unwrap().changes[0].lines, 2..=2);
+
+    ed.ensure_line_matching("y =", "y = 0", Anchor::Before("[b]".into()))
+        .ensure_line_matching("z =", "z = 0", Anchor::Start);
+    assert_eq!(ed.as_str(), "z = 0\n[a]\nx = 9\ny = 0\n[b]\nx = 2\n");
+}
+
+#[test]
+fn ensure_absent_removes_every_matching_line() {
+    let mut ed = Editor::from_string("keep\nDEBUG=1\nkeep\nDEBUG=2");
+    ed.ensure_absent("DEBUG");
+    assert_eq!(ed.as_str(), "keep\nkeep\n");
+    assert_eq!(ed.last_report().unwrap().matches(), 2);
+    ed.ensure_absent("DEBUG");
+    assert!(!ed.last_report().unwrap().changed);
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn regex_patterns() {
+    let re = regex::Regex::new(r"^\s*PermitRootLogin\b").unwrap();
+    let mut ed = Editor::from_string("Port 22\n  PermitRootLogin yes\n");
+    ed.ensure_line_matching(&re, "PermitRootLogin no", Anchor::End);
+    assert_eq!(ed.as_str(), "Port 22\nPermitRootLogin no\n");
+}
+
+#[test]
+fn pattern_not_matching_its_replacement_still_converges() {
+    let mut ed = Editor::from_string("Port 22\nPermitRootLogin yes\n");
+    for _ in 0..3 {
+        ed.ensure_line_matching("PermitRootLogin yes", "PermitRootLogin no", Anchor::End);
+    }
+    assert_eq!(ed.as_str(), "Port 22\nPermitRootLogin no\n");
+    assert!(!ed.last_report().unwrap().changed);
+}
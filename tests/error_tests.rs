@@ -0,0 +1,66 @@
+use std::error::Error as _;
+
+use file_editor::{Editor, Encoding, Error};
+
+#[test]
+fn io_errors_carry_the_path() {
+    let dir = tempfile::tempdir().unwrap();
+    let p = dir.path().join("missing.txt");
+
+    let err = Editor::open(&p).unwrap_err();
+    assert!(matches!(&err, Error::Io { path: Some(path), .. } if *path == p));
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    assert!(err.to_string().starts_with(&p.display().to_string()));
+    let source = err.source().unwrap().downcast_ref::<std::io::Error>();
+    assert_eq!(source.unwrap().kind(), std::io::ErrorKind::NotFound);
+}
+
+#[test]
+fn decoding_failure_is_an_encoding_error() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("bad.txt");
+    std::fs::write(&p, b"ok \xFF")?;
+
+    match Editor::open(&p).unwrap_err() {
+        Error::Encoding {
+            path,
+            encoding,
+            source,
+        } => {
+            assert_eq!(path.as_deref(), Some(p.as_path()));
+            assert_eq!(encoding, Encoding::Utf8);
+            assert!(source.is::<std::string::FromUtf8Error>());
+        }
+        other => panic!("unexpected {other:?}"),
+    }
+    Ok(())
+}
+
+#[test]
+fn converts_to_io_error_and_back() {
+    let mut ed = Editor::from_string("text");
+    let err: std::io::Error = ed.save().unwrap_err().into();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(matches!(Error::from(err), Error::Validation(_)));
+
+    fn uses_io_result(ed: &mut Editor) -> std::io::Result<()> {
+        ed.try_erase("absent", Default::default())?;
+        Ok(())
+    }
+    let err = uses_io_result(&mut ed).unwrap_err();
+    assert!(matches!(
+        Error::from(err),
+        Error::MatchCount { found: 0, .. }
+    ));
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn regex_errors_convert() {
+    fn compile(src: &str) -> file_editor::Result<regex::Regex> {
+        Ok(regex::Regex::new(src)?)
+    }
+    let err = compile("(").unwrap_err();
+    assert!(matches!(err, Error::Regex(_)));
+    assert!(err.source().is_some());
+}
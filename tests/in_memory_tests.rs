@@ -0,0 +1,48 @@
+use file_editor::Editor;
+
+#[test]
+fn edit_string_without_disk() {
+    let mut ed = Editor::from_string("user=alice\n");
+    ed.replace("alice", "bob").append("role=admin\n");
+
+    assert!(ed.path().is_none());
+    assert_eq!(ed.as_str(), "user=bob\nrole=admin\n");
+    assert_eq!(ed.into_string(), "user=bob\nrole=admin\n");
+}
+
+#[test]
+fn reader_and_writer() -> std::io::Result<()> {
+    let body: &[u8] = b"\xEF\xBB\xBFhello\r\n";
+    let mut ed = Editor::from_reader(body)?;
+    assert!(ed.has_bom());
+    ed.append("world\n");
+
+    let mut out = Vec::new();
+    ed.save_to(&mut out)?;
+    assert_eq!(out, b"\xEF\xBB\xBFhello\r\nworld\r\n");
+    Ok(())
+}
+
+#[test]
+fn save_without_path_is_an_error() {
+    let mut ed = Editor::from_string("x");
+    let err = ed.append("y").save().unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(err.to_string().contains("save_as"));
+    assert!(ed.rename("elsewhere").is_err());
+    assert!(ed.reload().is_err());
+}
+
+#[test]
+fn save_as_attaches_a_path() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("fixture.txt");
+
+    let mut ed = Editor::from_string("draft");
+    ed.save_as(&p)?;
+    assert_eq!(ed.path(), Some(p.as_path()));
+
+    ed.append(" 2").save()?;
+    assert_eq!(std::fs::read_to_string(&p)?, "draft 2");
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use file_editor::{Editor, Error, LineEnding, LineEndingPolicy};
+
+#[test]
+fn journal_records_every_change() {
+    let mut ed = Editor::from_string("host = a\nport = 80\n");
+    ed.record_journal(true)
+        .replace("80", "8080")
+        .insert_after("host = a", "user = b\n", false)
+        .erase("missing");
+    ed.undo();
+
+    let journal = ed.journal().unwrap();
+    let ops: Vec<_> = journal.into_iter().map(|e| e.op.as_str()).collect();
+    assert_eq!(ops, ["replace", "insert_after", "erase", "undo"]);
+
+    let replace = &journal.entries()[0];
+    assert_eq!(replace.pattern.as_deref(), Some("80"));
+    assert_eq!(replace.text.as_deref(), Some("8080"));
+    assert_eq!(replace.matches.len(), 1);
+    assert_eq!(replace.matches[0], 16..20);
+    assert_eq!(replace.edits[0].at, 16);
+    assert_eq!(replace.edits[0].removed, "80");
+    assert!(journal.entries()[2].edits.is_empty());
+}
+
+#[test]
+fn replay_reproduces_the_buffer() -> file_editor::Result<()> {
+    let original = "a\r\nb\r\n";
+    let mut live = Editor::from_string(original);
+    live.record_journal(true)
+        .append("c\n")
+        .line_ending_policy(LineEndingPolicy::Force(LineEnding::Lf))
+        .checkpoint("x")
+        .mask("b", "*")
+        .rollback_to("x")?
+        .prepend("# top\n");
+
+    let mut fresh = Editor::from_string(original);
+    fresh.replay(live.journal().unwrap())?;
+    assert_eq!(fresh.as_str(), live.as_str());
+    Ok(())
+}
+
+#[test]
+fn replay_refuses_a_different_buffer() {
+    let mut live = Editor::from_string("x = 1\n");
+    live.record_journal(true)
+        .replace("1", "2")
+        .append("y = 3\n");
+    let journal = live.take_journal().unwrap();
+    assert!(live.journal().unwrap().is_empty());
+
+    let mut other = Editor::from_string("x = 9\n");
+    let err = other.replay(&journal).unwrap_err();
+    assert!(matches!(err, Error::ReplayMismatch { entry: 0, .. }));
+    assert_eq!(other.as_str(), "x = 9\n");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn journal_round_trips_as_json_lines() -> std::io::Result<()> {
+    let mut ed = Editor::from_string("token = abc\n");
+    ed.record_journal(true)
+        .mask("abc", "***")
+        .append("\"quoted\"\n");
+    let journal = ed.journal().unwrap();
+
+    let mut out = Vec::new();
+    journal.write_json_lines(&mut out)?;
+    let text = String::from_utf8(out).unwrap();
+    assert_eq!(text.lines().count(), 2);
+    assert!(text.starts_with("{\"op\":\"mask\",\"pattern\":\"abc\""));
+
+    let back = file_editor::Journal::read_json_lines(text.as_bytes())?;
+    assert_eq!(&back, journal);
+    let mut fresh = Editor::from_string("token = abc\n");
+    fresh.replay(&back)?;
+    assert_eq!(fresh.as_str(), ed.as_str());
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn replay_refuses_overlapping_edits() -> std::io::Result<()> {
+    let mut ed = Editor::from_string("aa\n");
+    ed.record_journal(true).replace("a", "b");
+    let mut out = Vec::new();
+    ed.journal().unwrap().write_json_lines(&mut out)?;
+    let text = String::from_utf8(out).unwrap();
+    assert!(text.contains("\"at\":1,"));
+    // both edits now claim the first byte
+    let tampered = text.replace("\"at\":1,", "\"at\":0,");
+
+    let journal = file_editor::Journal::read_json_lines(tampered.as_bytes())?;
+    let mut fresh = Editor::from_string("aa\n");
+    let err = fresh.replay(&journal).unwrap_err();
+    assert!(matches!(err, Error::ReplayMismatch { entry: 0, .. }));
+    assert_eq!(fresh.as_str(), "aa\n");
+    Ok(())
+}
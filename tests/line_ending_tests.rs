@@ -0,0 +1,56 @@
+use file_editor::{Editor, LineEnding, LineEndingPolicy};
+
+#[test]
+fn crlf_file_stays_crlf() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("build.bat");
+    std::fs::write(&p, "@echo off\r\nset A=1\r\n")?;
+
+    let mut ed = Editor::open(&p)?;
+    assert_eq!(ed.line_ending(), LineEnding::CrLf);
+    ed.insert_after("@echo off", "set B=2\n", false)
+        .append("exit /b\n")
+        .replace("A=1", "A=1\nset C=3")
+        .save()?;
+
+    assert_eq!(
+        std::fs::read_to_string(&p)?,
+        "@echo off\r\nset B=2\r\nset A=1\r\nset C=3\r\nexit /b\r\n"
+    );
+    assert_eq!(ed.find_lines("set", None), vec![2, 3, 4]);
+    Ok(())
+}
+
+#[test]
+fn force_policy_converts_on_save() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("mixed.txt");
+    std::fs::write(&p, "a\r\nb\nc\r\n")?;
+
+    Editor::open(&p)?
+        .line_ending_policy(LineEndingPolicy::Force(LineEnding::Lf))
+        .save()?; // conversion alone marks the buffer dirty
+    assert_eq!(std::fs::read_to_string(&p)?, "a\nb\nc\n");
+
+    Editor::open(&p)?
+        .line_ending_policy(LineEndingPolicy::Force(LineEnding::CrLf))
+        .append("d\n")
+        .save()?;
+    assert_eq!(std::fs::read_to_string(&p)?, "a\r\nb\r\nc\r\nd\r\n");
+    Ok(())
+}
+
+#[test]
+fn keep_policy_leaves_mixed_endings() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("keep.txt");
+    std::fs::write(&p, "a\r\nb\nc\n")?;
+
+    Editor::open(&p)?
+        .line_ending_policy(LineEndingPolicy::Keep)
+        .prepend("0\r\n")
+        .save()?;
+    // dominant ending is LF, so inserted text is converted to LF
+    assert_eq!(std::fs::read_to_string(&p)?, "0\na\r\nb\nc\n");
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use std::ops::Bound;
+
+use file_editor::{Editor, Error};
+
+#[test]
+fn read_lines_by_number() {
+    let ed = Editor::from_string("one\r\ntwo\nthree");
+    assert_eq!(ed.line_count(), 3);
+    assert_eq!(ed.line(1), Some("one"));
+    assert_eq!(ed.line(3), Some("three"));
+    assert_eq!(ed.line(0), None);
+    assert_eq!(ed.line(4), None);
+    assert_eq!(ed.lines().collect::<Vec<_>>(), ["one", "two", "three"]);
+    assert_eq!(Editor::from_string("").line_count(), 0);
+}
+
+#[test]
+fn replace_and_insert_lines() -> std::io::Result<()> {
+    let mut ed = Editor::from_string("a\r\nb\r\nc\r\n");
+    ed.replace_line(2, "B")?
+        .insert_line_before(1, "start")?
+        .insert_line_after(4, "end")?
+        .insert_line_after(2, "a2")?;
+    assert_eq!(ed.as_str(), "start\r\na\r\na2\r\nB\r\nc\r\nend\r\n");
+
+    // a last line without a line ending gets one first
+    let mut ed = Editor::from_string("x");
+    ed.insert_line_after(1, "y")?;
+    assert_eq!(ed.as_str(), "x\ny");
+    Ok(())
+}
+
+#[test]
+fn delete_found_lines_and_ranges() -> std::io::Result<()> {
+    let text: String = (1..=10).map(|n| format!("line {n}\n")).collect();
+    let mut ed = Editor::from_string(text);
+    ed.delete_lines(8..)?
+        .delete_lines(2..=3)?
+        .delete_lines(..2)?;
+    assert_eq!(
+        ed.lines().collect::<Vec<_>>(),
+        ["line 4", "line 5", "line 6", "line 7"]
+    );
+
+    for n in ed.find_lines("line 5", None).into_iter().rev() {
+        ed.delete_lines(n..n + 1)?;
+    }
+    assert_eq!(ed.as_str(), "line 4\nline 6\nline 7\n");
+    Ok(())
+}
+
+#[test]
+fn swap_keeps_line_endings() -> std::io::Result<()> {
+    let mut ed = Editor::from_string("first\r\nmiddle\nlast");
+    ed.swap_lines(3, 1)?;
+    assert_eq!(ed.as_str(), "last\r\nmiddle\nfirst");
+    assert_eq!(ed.last_report().unwrap().matches(), 2);
+    ed.undo();
+    assert_eq!(ed.as_str(), "first\r\nmiddle\nlast");
+
+    ed.swap_lines(2, 2)?;
+    assert!(!ed.last_report().unwrap().changed);
+    Ok(())
+}
+
+#[test]
+fn out_of_range_lines_are_errors() {
+    let mut ed = Editor::from_string("only\n");
+    assert!(matches!(
+        ed.replace_line(2, "x").unwrap_err(),
+        Error::Validation(_)
+    ));
+    assert!(ed.insert_line_before(0, "x").is_err());
+    assert!(ed.delete_lines(1..=2).is_err());
+    assert!(ed.delete_lines(1..1).is_err());
+    let past_the_end = (Bound::Excluded(usize::MAX), Bound::Unbounded);
+    assert!(ed.delete_lines(past_the_end).is_err());
+    assert!(ed.swap_lines(1, 5).is_err());
+    assert_eq!(ed.as_str(), "only\n");
+}
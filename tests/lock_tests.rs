@@ -0,0 +1,103 @@
+use std::time::Duration;
+
+use file_editor::{Editor, EditorOptions, Error, Lock};
+
+const SIDECAR: Lock = Lock::Sidecar {
+    stale_after: Duration::from_secs(60),
+};
+
+#[test]
+fn advisory_lock_excludes_second_editor() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("jobs.conf");
+    std::fs::write(&p, "a\n")?;
+
+    let mut first = EditorOptions::new().lock(Lock::Advisory).open(&p)?;
+    assert!(first.is_locked());
+
+    let err = EditorOptions::new()
+        .lock(Lock::Advisory)
+        .lock_timeout(Duration::from_millis(30))
+        .open(&p)
+        .unwrap_err();
+    assert!(matches!(err, Error::Locked { holder: None, .. }));
+    assert_eq!(err.kind(), std::io::ErrorKind::WouldBlock);
+
+    // the lock follows the file across an atomic save
+    first.append("b\n").save()?;
+    assert!(EditorOptions::new().lock(Lock::Advisory).open(&p).is_err());
+
+    first.unlock();
+    let mut second = EditorOptions::new().lock(Lock::Advisory).open(&p)?;
+    second.append("c\n").save()?;
+    assert_eq!(std::fs::read_to_string(&p)?, "a\nb\nc\n");
+    Ok(())
+}
+
+#[test]
+fn sidecar_lock_released_on_drop() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("state.json");
+    let sidecar = dir.path().join("state.json.lock");
+    std::fs::write(&p, "{}")?;
+
+    {
+        let _ed = EditorOptions::new().lock(SIDECAR).open(&p)?;
+        assert!(sidecar.exists());
+
+        let err = EditorOptions::new().lock(SIDECAR).open(&p).unwrap_err();
+        assert!(matches!(
+            &err,
+            Error::Locked {
+                holder: Some(_),
+                ..
+            }
+        ));
+        assert!(err.to_string().contains(&std::process::id().to_string()));
+    }
+    assert!(!sidecar.exists());
+    EditorOptions::new().lock(SIDECAR).open(&p)?;
+    Ok(())
+}
+
+#[test]
+fn stale_sidecar_is_taken_over() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("stale.txt");
+    std::fs::write(&p, "")?;
+    std::fs::write(dir.path().join("stale.txt.lock"), "leftover")?;
+
+    let stale = Lock::Sidecar {
+        stale_after: Duration::ZERO,
+    };
+    std::thread::sleep(Duration::from_millis(5));
+    let ed = EditorOptions::new().lock(stale).open(&p)?;
+    assert!(ed.is_locked());
+    Ok(())
+}
+
+#[test]
+fn sidecar_works_for_lazy_files() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("new.txt");
+
+    let err = EditorOptions::new()
+        .create(true)
+        .lazy(true)
+        .lock(Lock::Advisory)
+        .open(&p)
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+    let mut ed = EditorOptions::new()
+        .create(true)
+        .lazy(true)
+        .lock(SIDECAR)
+        .open(&p)?;
+    ed.append("x").save()?;
+    assert!(
+        !Editor::clone(&ed).is_locked(),
+        "clones never share the lock"
+    );
+    Ok(())
+}
@@ -0,0 +1,146 @@
+use file_editor::{Editor, Error, MergeStrategy};
+
+const BASE: &str = "[a]\nx = 1\n\n[b]\ny = 2\n\n[c]\nz = 3\n";
+
+#[test]
+fn disjoint_changes_merge_on_save() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("app.ini");
+    std::fs::write(&p, BASE)?;
+
+    let mut ed = Editor::open(&p)?;
+    std::fs::write(&p, BASE.replace("z = 3", "z = 30"))?;
+
+    ed.merge_strategy(MergeStrategy::Markers)
+        .replace("x = 1", "x = 10")
+        .save()?;
+    assert_eq!(
+        std::fs::read_to_string(&p)?,
+        "[a]\nx = 10\n\n[b]\ny = 2\n\n[c]\nz = 30\n"
+    );
+    assert!(ed.last_save().unwrap().merged);
+
+    // in sync again: a plain save does not merge
+    ed.append("[d]\n").save()?;
+    assert!(!ed.last_save().unwrap().merged);
+    Ok(())
+}
+
+#[test]
+fn overlapping_changes_get_markers() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("app.ini");
+    std::fs::write(&p, BASE)?;
+
+    let mut ed = Editor::open(&p)?;
+    std::fs::write(&p, BASE.replace("y = 2", "y = theirs"))?;
+
+    let err = ed
+        .merge_strategy(MergeStrategy::Markers)
+        .replace("y = 2", "y = ours")
+        .save()
+        .unwrap_err();
+    assert!(matches!(&err, Error::MergeConflict { conflicts, .. } if conflicts.len() == 1));
+    assert_eq!(err.path(), Some(p.as_path()));
+    assert!(
+        ed.as_str()
+            .contains("[b]\n<<<<<<< ours\ny = ours\n=======\ny = theirs\n>>>>>>> theirs\n")
+    );
+    // nothing was written yet
+    assert_eq!(
+        std::fs::read_to_string(&p)?,
+        BASE.replace("y = 2", "y = theirs")
+    );
+
+    // resolve by hand, then save on top of their version
+    ed.replace(
+        "<<<<<<< ours\ny = ours\n=======\ny = theirs\n>>>>>>> theirs\n",
+        "y = both\n",
+    )
+    .save()?;
+    assert_eq!(
+        std::fs::read_to_string(&p)?,
+        BASE.replace("y = 2", "y = both")
+    );
+    Ok(())
+}
+
+#[test]
+fn report_strategy_lists_conflicts_and_keeps_buffer() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("app.ini");
+    std::fs::write(&p, BASE)?;
+
+    let mut ed = Editor::open(&p)?;
+    std::fs::write(&p, BASE.replace("x = 1", "x = 2"))?;
+    ed.merge_strategy(MergeStrategy::Report)
+        .replace("x = 1", "x = 3");
+    let before = ed.as_str().to_owned();
+
+    let Error::MergeConflict { conflicts, .. } = ed.save().unwrap_err() else {
+        panic!("expected a merge conflict");
+    };
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].line, 2);
+    assert_eq!(conflicts[0].base, "x = 1\n");
+    assert_eq!(conflicts[0].ours, "x = 3\n");
+    assert_eq!(conflicts[0].theirs, "x = 2\n");
+    assert_eq!(ed.as_str(), before);
+
+    // still a conflict until resolved some other way
+    assert!(ed.save().is_err());
+    ed.save_force()?;
+    assert_eq!(std::fs::read_to_string(&p)?, before);
+    Ok(())
+}
+
+#[test]
+fn refuse_is_the_default_and_deletion_never_merges() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("app.ini");
+    std::fs::write(&p, BASE)?;
+
+    let mut ed = Editor::open(&p)?;
+    std::fs::write(&p, format!("{BASE}[d]\n"))?;
+    assert!(matches!(
+        ed.prepend("# top\n").save().unwrap_err(),
+        Error::Conflict { .. }
+    ));
+
+    std::fs::remove_file(&p)?;
+    assert!(matches!(
+        ed.merge_strategy(MergeStrategy::Markers)
+            .save()
+            .unwrap_err(),
+        Error::Conflict { .. }
+    ));
+    Ok(())
+}
+
+#[test]
+fn merging_a_reformatted_large_file_stays_bounded() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("big.ini");
+    let base: String = (0..10_000).map(|i| format!("key{i} = {i}\n")).collect();
+    std::fs::write(&p, &base)?;
+
+    let mut ed = Editor::open(&p)?;
+    // someone else reformats almost every line on disk
+    let theirs: String = (0..10_000)
+        .map(|i| match i {
+            ..9_000 => format!("key{i}={i}\n"),
+            _ => format!("key{i} = {i}\n"),
+        })
+        .collect();
+    std::fs::write(&p, &theirs)?;
+
+    ed.merge_strategy(MergeStrategy::Markers)
+        .replace("key9999 = 9999", "key9999 = 0")
+        .save()?;
+    assert_eq!(
+        std::fs::read_to_string(&p)?,
+        theirs.replace("key9999 = 9999", "key9999 = 0")
+    );
+    assert!(ed.last_save().unwrap().merged);
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use file_editor::{Editor, Normalize};
+
+#[test]
+fn editorconfig_policy_on_save() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("gen.txt");
+    std::fs::write(&p, "first \n")?;
+
+    let mut ed = Editor::open(&p)?;
+    ed.normalize(Normalize::editorconfig())
+        .append("second\t\n\n\n")
+        .save()?;
+
+    assert_eq!(std::fs::read_to_string(&p)?, "first\nsecond\n");
+    assert_eq!(ed.as_str(), "first\nsecond\n");
+    let report = ed.last_save().unwrap();
+    assert!(report.written);
+    assert_eq!(report.bytes, 13);
+    assert_eq!(report.normalized.trimmed_lines, 2);
+    assert_eq!(report.normalized.removed_blank_lines, 2);
+    Ok(())
+}
+
+#[test]
+fn final_newline_uses_file_line_ending() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("win.txt");
+    std::fs::write(&p, "a\r\n")?;
+
+    let mut ed = Editor::open(&p)?;
+    ed.normalize(Normalize {
+        final_newline: true,
+        ..Normalize::default()
+    })
+    .erase("\r\n")
+    .append("b")
+    .save()?;
+
+    assert_eq!(std::fs::read_to_string(&p)?, "ab\r\n");
+    assert!(ed.last_save().unwrap().normalized.added_final_newline);
+    Ok(())
+}
+
+#[test]
+fn clean_save_is_reported_as_not_written() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("clean.txt");
+    std::fs::write(&p, "x\n")?;
+
+    let mut ed = Editor::open(&p)?;
+    assert!(ed.last_save().is_none());
+    ed.normalize(Normalize::editorconfig()).save()?;
+
+    let report = ed.last_save().unwrap();
+    assert!(!report.written);
+    assert_eq!(std::fs::read_to_string(&p)?, "x\n");
+    Ok(())
+}
+
+#[test]
+fn clean_buffer_is_normalized_on_save() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("untouched.txt");
+    std::fs::write(&p, "a  \nb\n\n")?;
+
+    let mut ed = Editor::open(&p)?;
+    ed.normalize(Normalize::editorconfig()).save()?;
+    assert_eq!(std::fs::read_to_string(&p)?, "a\nb\n");
+    let report = ed.last_save().unwrap();
+    assert!(report.written);
+    assert_eq!(report.normalized.trimmed_lines, 1);
+
+    // now conforming: the next save writes nothing
+    ed.save()?;
+    assert!(!ed.last_save().unwrap().written);
+    Ok(())
+}
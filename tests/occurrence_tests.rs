@@ -0,0 +1,73 @@
+use file_editor::{Editor, Error, Occurrence};
+
+const CARGO: &str =
+    "[package]\nname = \"demo\"\n\n[[bin]]\nname = \"a\"\n\n[[bin]]\nname = \"b\"\n";
+
+#[test]
+fn last_and_nth_occurrence() {
+    let mut ed = Editor::from_string(CARGO);
+    ed.insert_after(("[[bin]]", Occurrence::Last), "path = \"b.rs\"\n", false)
+        .insert_before(("name = ", Occurrence::Nth(1)), "# first bin\n", false);
+    assert_eq!(
+        ed.as_str(),
+        "[package]\nname = \"demo\"\n\n[[bin]]\n# first bin\nname = \"a\"\n\n\
+         [[bin]]\npath = \"b.rs\"\nname = \"b\"\n"
+    );
+}
+
+#[test]
+fn all_occurrences_despite_shifting_offsets() {
+    let src = "#[test]\nfn a() {}\n\n#[test]\nfn b() {}\n\n#[test]\nfn c() {}\n";
+    let mut ed = Editor::from_string(src);
+    ed.insert_after(("#[test]", Occurrence::All), "#[ignore]\n", false);
+    assert_eq!(
+        ed.as_str(),
+        src.replace("#[test]\n", "#[test]\n#[ignore]\n")
+    );
+    assert_eq!(ed.last_report().unwrap().matches(), 3);
+
+    ed.replace_marker(("#[ignore]", Occurrence::All), "#[should_panic]", false)
+        .insert_before(("fn ", Occurrence::All), "    ", false);
+    assert_eq!(ed.as_str().matches("#[should_panic]\n    fn ").count(), 3);
+
+    // a single undo reverts every occurrence of the last operation
+    ed.undo();
+    assert!(!ed.as_str().contains("    fn"));
+}
+
+#[test]
+fn range_of_occurrences() {
+    let mut ed = Editor::from_string("  - x\n  - x\n  - x\n  - x\n");
+    ed.replace_marker(("- x", Occurrence::Range(1..3)), "- y", false)
+        .insert_before(("  - y", Occurrence::All), "  # changed\n", false);
+    assert_eq!(
+        ed.as_str(),
+        "  - x\n  # changed\n  - y\n  # changed\n  - y\n  - x\n"
+    );
+}
+
+#[test]
+fn missing_occurrence_is_not_found() {
+    let mut ed = Editor::from_string("a\na\n");
+    ed.insert_after(("a", Occurrence::Nth(2)), "b", false);
+    assert_eq!(ed.as_str(), "a\na\n");
+
+    let err = ed
+        .try_replace_marker(("a", Occurrence::Nth(2)), "c", false)
+        .unwrap_err();
+    assert!(matches!(err, Error::NotFound { marker } if marker == "a"));
+    assert!(
+        ed.try_replace_marker(("a", Occurrence::Last), "c", false)
+            .is_ok()
+    );
+    assert_eq!(ed.as_str(), "a\nc\n");
+}
+
+#[test]
+fn plain_str_and_string_markers_still_mean_first() {
+    let marker = String::from("a");
+    let mut ed = Editor::from_string("a a");
+    ed.insert_after(&marker, "1", false)
+        .insert_before("a", "0", false);
+    assert_eq!(ed.as_str(), "0a1 a");
+}
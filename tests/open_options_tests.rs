@@ -0,0 +1,105 @@
+use file_editor::{Editor, EditorOptions};
+
+#[test]
+fn create_new_refuses_existing_file() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("once.txt");
+
+    Editor::create_new(&p)?.append("first run\n").save()?;
+    let err = Editor::create_new(&p).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+    assert_eq!(std::fs::read_to_string(&p)?, "first run\n");
+    Ok(())
+}
+
+#[test]
+fn open_or_create_keeps_contents() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("maybe.txt");
+
+    Editor::open_or_create(&p)?.append("a\n").save()?;
+    Editor::open_or_create(&p)?.append("b\n").save()?;
+    assert_eq!(std::fs::read_to_string(&p)?, "a\nb\n");
+
+    let missing = dir.path().join("missing.txt");
+    let err = Editor::open_with(&missing, &EditorOptions::new()).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    Ok(())
+}
+
+#[test]
+fn lazy_creation_with_parent_dirs() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("a/b/lazy.txt");
+
+    let mut ed = Editor::options()
+        .create(true)
+        .lazy(true)
+        .create_dirs(true)
+        .open(&p)?;
+    assert!(!p.exists() && !dir.path().join("a").exists());
+
+    ed.save()?; // lazily created editors start dirty
+    assert_eq!(std::fs::read_to_string(&p)?, "");
+    Ok(())
+}
+
+#[test]
+fn lazy_truncate_defers_until_save() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("t.txt");
+    std::fs::write(&p, "old")?;
+
+    let mut ed = Editor::options().truncate(true).lazy(true).open(&p)?;
+    assert_eq!(std::fs::read_to_string(&p)?, "old");
+    ed.append("new").save()?;
+    assert_eq!(std::fs::read_to_string(&p)?, "new");
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn mode_applies_to_new_files() -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let dir = tempfile::tempdir()?;
+    let eager = dir.path().join("eager.key");
+    let lazy = dir.path().join("lazy.key");
+
+    Editor::options()
+        .create_new(true)
+        .mode(0o600)
+        .open(&eager)?;
+    Editor::options()
+        .create_new(true)
+        .lazy(true)
+        .mode(0o640)
+        .open(&lazy)?
+        .save()?;
+
+    let mode = |p: &std::path::Path| std::fs::metadata(p).map(|m| m.permissions().mode() & 0o777);
+    assert_eq!(mode(&eager)?, 0o600);
+    assert_eq!(mode(&lazy)?, 0o640);
+    Ok(())
+}
+
+#[test]
+fn concurrent_open_or_create_all_succeed() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("shared.txt");
+    let start = std::sync::Barrier::new(8);
+    std::thread::scope(|s| {
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                s.spawn(|| {
+                    start.wait();
+                    Editor::open_or_create(&p).map(drop)
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .try_for_each(|h| h.join().expect("opener panicked"))
+    })?;
+    assert_eq!(std::fs::read_to_string(&p)?, "");
+    Ok(())
+}
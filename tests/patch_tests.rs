@@ -0,0 +1,164 @@
+use file_editor::{Editor, Error, HunkStatus, PatchOptions};
+
+const BASE: &str = "one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\nnine\nten\n";
+
+#[test]
+fn clean_patch_applies_in_place() -> std::io::Result<()> {
+    let mut ed = Editor::from_string(BASE);
+    let patch = "--- a/n.txt\n+++ b/n.txt\n\
+                 @@ -2,3 +2,3 @@\n two\n-three\n+THREE\n four\n\
+                 @@ -8,2 +8,3 @@\n eight\n nine\n+nine and a half\n";
+    let report = ed.apply_patch(patch)?;
+    assert!(report.is_clean());
+    assert_eq!(report.applied(), 2);
+    assert_eq!(
+        ed.as_str(),
+        "one\ntwo\nTHREE\nfour\nfive\nsix\nseven\neight\nnine\nnine and a half\nten\n"
+    );
+    assert_eq!(ed.last_report().unwrap().op, "apply_patch");
+
+    // one undo step for the whole patch
+    ed.undo();
+    assert_eq!(ed.as_str(), BASE);
+    Ok(())
+}
+
+#[test]
+fn shifted_hunk_applies_with_offset() -> std::io::Result<()> {
+    let mut ed = Editor::from_string(format!("header\nheader\n{BASE}"));
+    let report = ed.apply_patch("--- f\n+++ f\n@@ -4,3 +4,3 @@\n four\n-five\n+FIVE\n six\n")?;
+    assert_eq!(
+        report.hunks,
+        [HunkStatus::AppliedWithOffset { offset: 2, fuzz: 0 }]
+    );
+    assert!(ed.as_str().contains("four\nFIVE\nsix\n"));
+
+    let mut strict = Editor::from_string(format!("header\nheader\n{BASE}"));
+    let report = strict.apply_patch_with(
+        "--- f\n+++ f\n@@ -4,3 +4,3 @@\n four\n-five\n+FIVE\n six\n",
+        &PatchOptions {
+            max_offset: 1,
+            ..PatchOptions::default()
+        },
+    )?;
+    assert_eq!(report.hunks, [HunkStatus::Rejected]);
+    assert!(strict.as_str().starts_with("header\nheader\none\n"));
+    assert!(strict.as_str().contains("five\n"));
+    Ok(())
+}
+
+#[test]
+fn fuzz_ignores_outer_context() -> std::io::Result<()> {
+    let mut ed = Editor::from_string(BASE.replace("four", "4"));
+    let patch = "--- f\n+++ f\n@@ -4,3 +4,3 @@\n four\n-five\n+FIVE\n six\n";
+    let report = ed.apply_patch(patch)?;
+    assert_eq!(
+        report.hunks,
+        [HunkStatus::AppliedWithOffset { offset: 0, fuzz: 1 }]
+    );
+    assert!(ed.as_str().contains("4\nFIVE\nsix\n"));
+
+    let mut exact = Editor::from_string(BASE.replace("four", "4"));
+    let opts = PatchOptions {
+        fuzz: 0,
+        ..PatchOptions::default()
+    };
+    assert_eq!(exact.apply_patch_with(patch, &opts)?.rejected(), 1);
+    Ok(())
+}
+
+#[test]
+fn rejected_hunks_go_to_rej_file() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("n.txt");
+    std::fs::write(&p, BASE)?;
+
+    let bad = "@@ -6,1 +6,1 @@\n-zzz\n+ZZZ\n";
+    let patch = format!("--- a/n.txt\n+++ b/n.txt\n@@ -1 +1 @@\n-one\n+ONE\n{bad}");
+    let mut ed = Editor::open(&p)?;
+    let report = ed.apply_patch_with(
+        &patch,
+        &PatchOptions {
+            reject_file: true,
+            ..PatchOptions::default()
+        },
+    )?;
+    assert_eq!(report.hunks, [HunkStatus::Applied, HunkStatus::Rejected]);
+    let rej = dir.path().join("n.txt.rej");
+    assert_eq!(report.reject_file.as_deref(), Some(rej.as_path()));
+    assert_eq!(
+        std::fs::read_to_string(&rej)?,
+        format!("--- a/n.txt\n+++ b/n.txt\n{bad}")
+    );
+
+    ed.save()?;
+    assert!(std::fs::read_to_string(&p)?.starts_with("ONE\ntwo\n"));
+    Ok(())
+}
+
+#[test]
+fn own_diff_round_trips() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("cfg");
+    std::fs::write(&p, "a = 1\nb = 2\nc = 3")?;
+
+    let mut ed = Editor::open(&p)?;
+    ed.replace("b = 2", "b = 20").append("\nd = 4\n");
+    let diff = ed.diff();
+
+    let mut other = Editor::open(&p)?;
+    assert!(other.apply_patch(&diff)?.is_clean());
+    assert_eq!(other.as_str(), ed.as_str());
+    Ok(())
+}
+
+#[test]
+fn picks_section_for_own_path() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("b.txt");
+    std::fs::write(&p, "x\n")?;
+    let patch = "diff --git a/a.txt b/a.txt\n--- a/a.txt\n+++ b/a.txt\n@@ -1 +1 @@\n-x\n+A\n\
+                 diff --git a/b.txt b/b.txt\n--- a/b.txt\n+++ b/b.txt\n@@ -1 +1 @@\n-x\n+B\n";
+    let mut ed = Editor::open(&p)?;
+    ed.apply_patch(patch)?;
+    assert_eq!(ed.as_str(), "B\n");
+
+    let err = Editor::from_string("x\n").apply_patch(patch).unwrap_err();
+    assert!(matches!(err, Error::Validation(_)));
+    Ok(())
+}
+
+#[test]
+fn malformed_patch_is_an_error() {
+    let mut ed = Editor::from_string(BASE);
+    let err = ed
+        .apply_patch("--- f\n+++ f\n@@ -1,2 +1,2 @@\n one\n")
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidPatch { line: 5, .. }));
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(matches!(
+        ed.apply_patch("just text\n").unwrap_err(),
+        Error::InvalidPatch { .. }
+    ));
+}
+
+#[test]
+fn dry_run_writes_no_rej_file() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("n.txt");
+    std::fs::write(&p, BASE)?;
+
+    let patch = "--- a/n.txt\n+++ b/n.txt\n@@ -6,1 +6,1 @@\n-zzz\n+ZZZ\n";
+    let mut ed = Editor::open(&p)?;
+    let report = ed.dry_run(true).apply_patch_with(
+        patch,
+        &PatchOptions {
+            reject_file: true,
+            ..PatchOptions::default()
+        },
+    )?;
+    assert_eq!(report.rejected(), 1);
+    assert_eq!(report.reject_file, None);
+    assert!(!dir.path().join("n.txt.rej").exists());
+    Ok(())
+}
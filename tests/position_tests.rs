@@ -0,0 +1,56 @@
+use file_editor::{Editor, Error, Position, Span};
+
+#[test]
+fn fix_spots_reported_by_a_linter() -> std::io::Result<()> {
+    let mut ed = Editor::from_string("fn f() {\r\n    let x = 1\r\n    x\r\n}\r\n");
+    // "missing semicolon at 2:14", "unnecessary `x` at 3:5-3:6"
+    ed.insert_at(Position::new(2, 14), ";")?;
+    ed.replace_range(Span::new((3, 5), (3, 6)), "x + 0")?;
+    assert_eq!(
+        ed.as_str(),
+        "fn f() {\r\n    let x = 1;\r\n    x + 0\r\n}\r\n"
+    );
+
+    // whole-line deletion swallows the CRLF
+    ed.delete_range(Span::new((2, 1), (3, 1)))?;
+    assert_eq!(ed.as_str(), "fn f() {\r\n    x + 0\r\n}\r\n");
+    assert_eq!(ed.last_report().unwrap().op, "delete_range");
+
+    ed.undo();
+    assert!(ed.as_str().contains("let x = 1;"));
+    Ok(())
+}
+
+#[test]
+fn multibyte_columns_and_inserted_newlines() -> std::io::Result<()> {
+    let mut ed = Editor::from_string("naïve café\r\n");
+    ed.insert_at((1, 6), "\nlater")?;
+    // inserted text follows the file's line ending
+    assert_eq!(ed.as_str(), "naïve\r\nlater café\r\n");
+    Ok(())
+}
+
+#[test]
+fn offsets_and_positions_convert_both_ways() -> std::io::Result<()> {
+    let ed = Editor::from_string("αβ\ngamma\n");
+    assert_eq!(ed.offset_of((2, 3))?, 7);
+    assert_eq!(ed.position_of(7)?, Position::new(2, 3));
+    assert_eq!(ed.position_of(ed.as_str().len())?, Position::new(3, 1));
+
+    let lines = ed.find_lines("gamma", None);
+    assert_eq!(ed.offset_of((lines[0], 1))?, 5);
+    Ok(())
+}
+
+#[test]
+fn invalid_positions_are_rejected() {
+    let mut ed = Editor::from_string("short\n");
+    for pos in [(0, 1), (1, 0), (1, 7), (3, 1)] {
+        let err = ed.insert_at(pos, "x").unwrap_err();
+        assert!(matches!(err, Error::Validation(_)), "{pos:?}");
+    }
+    let err = ed.delete_range(Span::new((1, 4), (1, 2))).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(ed.position_of(42).is_err());
+    assert_eq!(ed.as_str(), "short\n");
+}
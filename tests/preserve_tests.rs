@@ -0,0 +1,113 @@
+#![cfg(unix)]
+
+use file_editor::{Editor, Preserve};
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+#[test]
+fn permissions_survive_save() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("run.sh");
+    std::fs::write(&p, "#!/bin/sh\necho hi\n")?;
+    std::fs::set_permissions(&p, std::fs::Permissions::from_mode(0o751))?;
+
+    Editor::open(&p)?.append("echo bye\n").save()?;
+
+    assert_eq!(std::fs::metadata(&p)?.permissions().mode() & 0o7777, 0o751);
+    Ok(())
+}
+
+#[test]
+fn preserve_none_resets_permissions() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("plain.sh");
+    std::fs::write(&p, "x")?;
+    std::fs::set_permissions(&p, std::fs::Permissions::from_mode(0o700))?;
+
+    Editor::open(&p)?
+        .preserve(Preserve::none())
+        .append("y")
+        .save()?;
+
+    assert_ne!(std::fs::metadata(&p)?.permissions().mode() & 0o777, 0o700);
+    Ok(())
+}
+
+#[test]
+fn symlink_target_is_edited() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let real = dir.path().join("real.conf");
+    let link = dir.path().join("link.conf");
+    std::fs::write(&real, "a\n")?;
+    std::os::unix::fs::symlink("real.conf", &link)?;
+
+    Editor::open(&link)?.append("b\n").save()?;
+
+    assert!(std::fs::symlink_metadata(&link)?.file_type().is_symlink());
+    assert_eq!(std::fs::read_to_string(&real)?, "a\nb\n");
+
+    // without following, the link is replaced by a regular file
+    Editor::open(&link)?
+        .preserve(Preserve {
+            follow_symlinks: false,
+            ..Preserve::default()
+        })
+        .append("c\n")
+        .save()?;
+    assert!(std::fs::symlink_metadata(&link)?.file_type().is_file());
+    assert_eq!(std::fs::read_to_string(&real)?, "a\nb\n");
+    Ok(())
+}
+
+#[test]
+fn renaming_relative_symlink_keeps_it_valid() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    std::fs::create_dir(dir.path().join("sub"))?;
+    let real = dir.path().join("real.txt");
+    let link = dir.path().join("link.txt");
+    let moved = dir.path().join("sub/link.txt");
+    std::fs::write(&real, "data")?;
+    std::os::unix::fs::symlink("real.txt", &link)?;
+
+    Editor::open(&link)?.rename(&moved)?.append("!").save()?;
+
+    assert!(!link.exists());
+    assert_eq!(std::fs::read_to_string(&moved)?, "data!");
+    assert_eq!(std::fs::read_to_string(&real)?, "data!");
+    Ok(())
+}
+
+/// Ownership can only be handed to another user by root; skipped otherwise.
+#[test]
+fn ownership_survives_save() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("owned.conf");
+    std::fs::write(&p, "x")?;
+    if std::os::unix::fs::chown(&p, Some(4242), Some(4242)).is_err() {
+        return Ok(());
+    }
+
+    Editor::open(&p)?.append("y").save()?;
+
+    let meta = std::fs::metadata(&p)?;
+    assert_eq!((meta.uid(), meta.gid()), (4242, 4242));
+    Ok(())
+}
+
+#[cfg(feature = "xattr")]
+#[test]
+fn xattrs_survive_save() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("attrs.txt");
+    std::fs::write(&p, "x")?;
+    if xattr::set(&p, "user.origin", b"deploy").is_err() {
+        return Ok(()); // filesystem without user xattrs
+    }
+
+    Editor::open(&p)?.append("y").save()?;
+
+    assert_eq!(
+        xattr::get(&p, "user.origin")?.as_deref(),
+        Some(&b"deploy"[..])
+    );
+    Ok(())
+}
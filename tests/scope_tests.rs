@@ -0,0 +1,111 @@
+use file_editor::Editor;
+
+const CARGO: &str = "\
+[profile.dev]
+debug = true
+
+[profile.release]
+debug = true
+lto = false
+
+[profile.bench]
+debug = true
+";
+
+#[test]
+fn replace_only_inside_a_section() {
+    let mut ed = Editor::from_string(CARGO);
+    let mut release = ed.within("[profile.release]", "\n[");
+    assert_eq!(release.find_lines("debug", None), [5]);
+    release.replace("debug = true", "debug = false");
+    assert_eq!(ed.find_lines("debug = false", None), [5]);
+    assert_eq!(ed.find_lines("debug = true", None), [2, 9]);
+    assert_eq!(ed.last_report().unwrap().matches(), 1);
+}
+
+#[test]
+fn last_section_runs_to_end_of_buffer() {
+    let mut ed = Editor::from_string(CARGO);
+    ed.within("[profile.bench]", "\n[").erase("debug = true\n");
+    assert_eq!(ed.as_str(), CARGO.trim_end_matches("debug = true\n"));
+}
+
+#[test]
+fn repeated_regions_and_inclusive_bounds() {
+    let text =
+        "x\n# BEGIN secrets\nkey=1\n# END secrets\nkey=2\n# BEGIN secrets\nkey=3\n# END secrets\n";
+    let mut ed = Editor::from_string(text);
+    {
+        let mut secrets = ed.within("# BEGIN secrets", "# END secrets");
+        assert_eq!(secrets.regions().len(), 2);
+        secrets.mask("key=", "***=");
+    }
+    assert_eq!(
+        ed.as_str(),
+        text.replace("key=1", "***=1").replace("key=3", "***=3")
+    );
+
+    ed.within("# BEGIN secrets", "# END secrets")
+        .inclusive(true)
+        .replace("secrets", "vault");
+    assert_eq!(ed.as_str().matches("vault").count(), 4);
+    assert!(
+        ed.as_str()
+            .starts_with("x\n# BEGIN vault\n***=1\n# END vault\n")
+    );
+}
+
+#[test]
+fn nested_markers_are_balanced() {
+    let mut ed = Editor::from_string("a {\n  b {\n    v\n  }\n  v\n}\nv\n");
+    let mut braces = ed.within("{", "}");
+    assert_eq!(braces.regions().len(), 1);
+    assert_eq!(braces.find_lines("v", None), [3, 5]);
+    braces.replace("v", "w");
+    assert_eq!(ed.as_str(), "a {\n  b {\n    w\n  }\n  w\n}\nv\n");
+
+    // scopes narrow further
+    ed.within("a {", "\n}").within("b {", "}").replace("w", "z");
+    assert_eq!(ed.as_str(), "a {\n  b {\n    z\n  }\n  w\n}\nv\n");
+}
+
+#[test]
+fn missing_start_marker_changes_nothing() {
+    let mut ed = Editor::from_string(CARGO);
+    ed.within("[workspace]", "\n[")
+        .replace("debug", "opt-level");
+    assert_eq!(ed.as_str(), CARGO);
+    assert!(!ed.last_report().unwrap().changed);
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn regex_bounds_and_adjacent_regions() {
+    let start = regex::Regex::new(r"(?m)^\[profile\.(release|bench)\]").unwrap();
+    let end = regex::Regex::new(r"\n\[").unwrap();
+    let val = regex::Regex::new(r"debug = (\w+)").unwrap();
+    let mut ed = Editor::from_string(CARGO);
+    ed.within(&start, &end).replace(&val, "debug = !$1");
+    assert_eq!(ed.find_lines("!true", None), [5, 9]);
+}
+
+#[test]
+fn identical_fences_pair_up() {
+    let mut ed = Editor::from_string("---\na\n---\nb\n---\nc\n---\n");
+    let mut fenced = ed.within("---", "---");
+    assert_eq!(fenced.regions().len(), 2);
+    fenced.replace("\n", "\n> ");
+    assert_eq!(ed.as_str(), "---\n> a\n> ---\nb\n---\n> c\n> ---\n");
+}
+
+#[test]
+fn overlapping_inclusive_regions_are_merged() {
+    let mut ed = Editor::from_string("[a]\nx\n[b]\ny\n");
+    let mut sections = ed.within("[", "\n[");
+    sections.inclusive(true);
+    let regions = sections.regions();
+    assert_eq!(regions.len(), 1);
+    assert_eq!((regions[0].start, regions[0].end), (0, 12));
+    sections.replace("[", "(");
+    assert_eq!(ed.as_str(), "(a]\nx\n(b]\ny\n");
+}
@@ -0,0 +1,66 @@
+use file_editor::{Editor, Error, Expect};
+
+#[test]
+fn missing_marker_is_an_error() {
+    let mut ed = Editor::from_string("[deps]\n");
+    let err = ed
+        .try_insert_after("[dependencies]", "serde = \"1\"", false)
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    assert!(matches!(&err, Error::NotFound { marker } if marker == "[dependencies]"));
+    assert!(ed.try_insert_before("nope", "x", false).is_err());
+    assert!(ed.try_replace_marker("nope", "x", false).is_err());
+    assert_eq!(ed.as_str(), "[deps]\n");
+}
+
+#[test]
+fn present_marker_edits_as_usual() -> std::io::Result<()> {
+    let mut ed = Editor::from_string("a\nmark\nz\n");
+    ed.try_insert_before("mark", "b\n", false)?
+        .try_insert_after("mark", "y\n", false)?
+        .try_replace_marker("mark", "m", false)?;
+    assert_eq!(ed.as_str(), "a\nb\nm\ny\nz\n");
+    Ok(())
+}
+
+#[test]
+fn match_count_is_checked_before_editing() {
+    let mut ed = Editor::from_string("x=1 x=1 x=1");
+    let err = ed
+        .try_replace("x=1", "x=2", Expect::Exactly(2))
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(matches!(
+        &err,
+        Error::MatchCount {
+            pattern,
+            expected: Expect::Exactly(2),
+            found: 3,
+        } if pattern == "x=1"
+    ));
+    assert!(
+        err.to_string()
+            .contains("matched 3 times, expected exactly 2")
+    );
+    assert_eq!(ed.as_str(), "x=1 x=1 x=1");
+
+    assert!(ed.try_erase("y", Expect::default()).is_err());
+    assert!(ed.try_erase("y", Expect::Any).is_ok());
+    ed.try_mask("x=1", "***", Expect::AtLeast(3)).unwrap();
+    assert_eq!(ed.as_str(), "*** *** ***");
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn regex_patterns_are_counted() -> std::io::Result<()> {
+    let re = regex::Regex::new(r"\d+").unwrap();
+    let mut ed = Editor::from_string("a1 b22 c333");
+    let err = ed.try_erase(&re, Expect::Exactly(2)).unwrap_err();
+    assert!(matches!(
+        &err,
+        Error::MatchCount { pattern, found: 3, .. } if pattern == r"\d+"
+    ));
+    ed.try_erase(&re, Expect::Exactly(3))?;
+    assert_eq!(ed.as_str(), "a b c");
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use file_editor::{Editor, Normalize};
+
+#[test]
+fn undo_and_redo_walk_the_history() {
+    let mut ed = Editor::from_string("[server]\nport = 80\n");
+    ed.insert_after("[server]", "host = a\n", false)
+        .replace("80", "8080")
+        .prepend("# cfg\n")
+        .erase("nothing");
+    let done = ed.as_str().to_owned();
+    assert_eq!(done, "# cfg\n[server]\nhost = a\nport = 8080\n");
+
+    assert!(ed.undo());
+    assert!(ed.undo());
+    assert_eq!(ed.as_str(), "[server]\nhost = a\nport = 80\n");
+    assert!(ed.undo());
+    assert!(!ed.can_undo());
+    assert!(!ed.undo());
+    assert_eq!(ed.as_str(), "[server]\nport = 80\n");
+
+    while ed.redo() {}
+    assert_eq!(ed.as_str(), done);
+    assert!(!ed.can_redo());
+}
+
+#[test]
+fn new_edit_discards_redo_and_limit_bounds_undo() {
+    let mut ed = Editor::from_string("");
+    ed.history_limit(2);
+    ed.append("a").append("b").append("c");
+    assert!(ed.undo() && ed.undo());
+    assert!(!ed.undo());
+    assert_eq!(ed.as_str(), "a");
+
+    ed.append("x");
+    assert!(!ed.can_redo());
+    assert!(ed.undo());
+    assert_eq!(ed.as_str(), "a");
+}
+
+#[test]
+fn undo_tracks_dirty_state() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let p = dir.path().join("f.txt");
+    std::fs::write(&p, "v1\n")?;
+    let before = std::fs::metadata(&p)?.modified()?;
+
+    let mut ed = Editor::open(&p)?;
+    ed.replace("v1", "v2").undo();
+    ed.save()?;
+    assert!(!ed.last_save().unwrap().written);
+    assert_eq!(std::fs::metadata(&p)?.modified()?, before);
+
+    // undoing past a save makes the buffer differ from the file again
+    ed.redo();
+    ed.save()?;
+    assert!(ed.undo());
+    ed.save()?;
+    assert_eq!(std::fs::read_to_string(&p)?, "v1\n");
+    Ok(())
+}
+
+#[test]
+fn save_time_normalization_is_undoable() -> std::io::Result<()> {
+    let mut ed = Editor::from_string("a  \n\n\n");
+    ed.normalize(Normalize::editorconfig())
+        .save_to(std::io::sink())?;
+    assert_eq!(ed.as_str(), "a\n");
+    assert!(ed.undo());
+    assert_eq!(ed.as_str(), "a  \n\n\n");
+    Ok(())
+}